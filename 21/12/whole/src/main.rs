@@ -1,5 +1,6 @@
 use std::collections::{HashMap, HashSet};
 use utils::read_input_by_lines;
+use utils::dot::{DotGraph, ToDot};
 
 type MapType = HashMap<String, Vec<String>>;
 
@@ -13,6 +14,37 @@ impl Graph {
     }
 }
 
+fn is_small_cave(name: &str) -> bool {
+    name.chars().all(char::is_lowercase)
+}
+
+impl ToDot for Graph {
+    fn to_dot(&self) -> DotGraph {
+        let mut dot = DotGraph::new("caves", false);
+
+        let mut names: Vec<_> = self.graph.keys().collect();
+        names.sort();
+
+        for name in &names {
+            let shape = match name.as_str() {
+                "start" | "end" => "doublecircle",
+                small if is_small_cave(small) => "circle",
+                _ => "box",
+            };
+
+            dot.add_node(name, None, &[("shape", shape)]);
+        }
+
+        for name in names {
+            for neighbour in &self.graph[name] {
+                dot.add_edge(name, neighbour, None);
+            }
+        }
+
+        dot
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 struct Path {
     nodes: Vec<String>,
@@ -36,7 +68,7 @@ impl Path {
     fn add_node(&self, new_node: String) -> Path {
         let mut new_path = self.clone();
 
-        if new_node.chars().all(char::is_lowercase) {
+        if is_small_cave(&new_node) {
             let already_visited = new_path.visited.insert(new_node.clone());
             if !already_visited {
                 new_path.double_visited = Some(new_node.clone())
@@ -100,3 +132,35 @@ fn main() {
     dfs(&graph);
 }
 
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use utils::dot::ToDot;
+
+    use super::{Graph, MapType};
+
+    #[test]
+    fn dot_output() {
+        const EXPECTED: &str = r#"graph "caves" {
+    "A" [shape="box"];
+    "b" [shape="circle", color="red", penwidth="2"];
+    "end" [shape="doublecircle", color="red", penwidth="2"];
+    "start" [shape="doublecircle", color="red", penwidth="2"];
+    "A" -- "start";
+    "A" -- "b";
+    "A" -- "end";
+    "b" -- "start" [color="red", penwidth="2"];
+    "b" -- "end" [color="red", penwidth="2"];
+}
+"#;
+        let mut map: MapType = HashMap::new();
+        for (left, right) in [("start", "A"), ("start", "b"), ("A", "b"), ("A", "end"), ("b", "end")] {
+            map.entry(left.to_owned()).or_default().push(right.to_owned());
+            map.entry(right.to_owned()).or_default().push(left.to_owned());
+        }
+
+        let dot = Graph::new(map).to_dot_with_path(&["start", "b", "end"]).to_string();
+
+        assert_eq!(dot, EXPECTED);
+    }
+}
@@ -1,6 +1,10 @@
 use std::fmt::{Display, Formatter};
+use std::iter::Peekable;
+use std::str::Chars;
 
-#[derive(PartialEq, Debug)]
+use utils::read_input_by_lines;
+
+#[derive(PartialEq, Debug, Clone)]
 enum Node {
     Leaf(u32),
     Inner(Box<Node>, Box<Node>),
@@ -15,41 +19,169 @@ impl Display for Node {
     }
 }
 
+impl From<&str> for Node {
+    fn from(input: &str) -> Self {
+        let mut chars = input.trim().chars().peekable();
+
+        let node = Node::parse(&mut chars);
+        assert_eq!(chars.next(), None, "trailing characters in {}", input);
+
+        node
+    }
+}
+
 impl Node {
-    fn explode(&self) {
-        let mut stack : Vec<&Node>;
+    fn parse(chars: &mut Peekable<Chars>) -> Self {
+        match chars.next() {
+            Some('[') => {
+                let left = Node::parse(chars);
+                assert_eq!(chars.next(), Some(','), "expected ','");
+                let right = Node::parse(chars);
+                assert_eq!(chars.next(), Some(']'), "expected ']'");
+
+                Node::Inner(Box::new(left), Box::new(right))
+            }
+            Some(c) if c.is_ascii_digit() => {
+                let mut value = c.to_digit(10).unwrap();
+                while let Some(digit) = chars.next_if(|c| c.is_ascii_digit()) {
+                    value = value * 10 + digit.to_digit(10).unwrap();
+                }
+
+                Node::Leaf(value)
+            }
+            c => panic!("unexpected {:?}", c),
+        }
+    }
+
+    fn add_leftmost(&mut self, value: u32) {
+        match self {
+            Node::Leaf(leaf) => *leaf += value,
+            Node::Inner(l, _) => l.add_leftmost(value),
+        }
+    }
+
+    fn add_rightmost(&mut self, value: u32) {
+        match self {
+            Node::Leaf(leaf) => *leaf += value,
+            Node::Inner(_, r) => r.add_rightmost(value),
+        }
+    }
+
+    /// Explodes the leftmost pair nested in four pairs and returns the values that still have to
+    /// be added to the regular numbers left and right of it.
+    fn explode_at(&mut self, depth: usize) -> Option<(Option<u32>, Option<u32>)> {
+        match self {
+            Node::Leaf(_) => None,
+            Node::Inner(l, r) => {
+                if depth >= 4 {
+                    if let (Node::Leaf(a), Node::Leaf(b)) = (l.as_ref(), r.as_ref()) {
+                        let carry = (Some(*a), Some(*b));
+                        *self = Node::Leaf(0);
+                        return Some(carry);
+                    }
+                }
 
-        stack.push(&self);
+                if let Some((left, right)) = l.explode_at(depth + 1) {
+                    if let Some(value) = right {
+                        r.add_leftmost(value);
+                    }
+                    return Some((left, None));
+                }
 
+                if let Some((left, right)) = r.explode_at(depth + 1) {
+                    if let Some(value) = left {
+                        l.add_rightmost(value);
+                    }
+                    return Some((None, right));
+                }
 
+                None
+            }
+        }
+    }
+
+    fn explode(&mut self) -> bool {
+        self.explode_at(0).is_some()
     }
 
-    // fn split(&self) -> Option<Self> {
-    //     None
-    // }
-    //
-    // fn reduce(self) -> Self {
-    //     loop {}
-    //
-    //     self
-    // }
+    /// Splits the leftmost regular number of 10 or more.
+    fn split(&mut self) -> bool {
+        match self {
+            Node::Leaf(value) if *value >= 10 => {
+                let value = *value;
+                *self = Node::Inner(
+                    Box::new(Node::Leaf(value / 2)),
+                    Box::new(Node::Leaf(value.div_ceil(2))),
+                );
+                true
+            }
+            Node::Leaf(_) => false,
+            Node::Inner(l, r) => l.split() || r.split(),
+        }
+    }
+
+    /// Applies a single explode or, if nothing can explode, a single split.
+    fn reduce_step(&mut self) -> bool {
+        self.explode() || self.split()
+    }
+
+    fn reduce(mut self) -> Self {
+        while self.reduce_step() {}
+
+        self
+    }
+
+    fn magnitude(&self) -> u32 {
+        match self {
+            Node::Leaf(value) => *value,
+            Node::Inner(l, r) => 3 * l.magnitude() + 2 * r.magnitude(),
+        }
+    }
 }
 
 impl std::ops::Add for Box<Node> {
     type Output = Box<Node>;
 
     fn add(self, rhs: Self) -> Self::Output {
-        Box::new(Node::Inner(self, rhs))
+        Box::new(Node::Inner(self, rhs).reduce())
     }
 }
 
-fn main() {}
+fn sum(numbers: impl IntoIterator<Item = Box<Node>>) -> Option<Box<Node>> {
+    numbers.into_iter().reduce(|acc, number| acc + number)
+}
+
+/// Largest magnitude of the sum of two different numbers, in either order.
+fn largest_magnitude(numbers: &[Box<Node>]) -> u32 {
+    let mut largest = 0;
 
+    for (i, a) in numbers.iter().enumerate() {
+        for (j, b) in numbers.iter().enumerate() {
+            if i != j {
+                largest = largest.max((a.clone() + b.clone()).magnitude());
+            }
+        }
+    }
+
+    largest
+}
+
+fn main() {
+    let numbers: Vec<_> = read_input_by_lines()
+        .map(|line| Box::new(Node::from(line.as_str())))
+        .collect();
+
+    let total = sum(numbers.clone()).expect("no snailfish numbers");
+
+    println!("Sum {}", total);
+    println!("Magnitude {}", total.magnitude());
+    println!("Largest magnitude of two numbers {}", largest_magnitude(&numbers));
+}
 
 #[cfg(test)]
 mod tests {
-    use crate::Node;
-    use std::fmt::{Error, Write};
+    use crate::{largest_magnitude, sum, Node};
+    use std::fmt::Write;
 
     #[test]
     fn test_add() {
@@ -70,7 +202,7 @@ mod tests {
 
         let mut test = String::new();
 
-        write!(&mut test, "{}", add);
+        write!(&mut test, "{}", add)?;
 
         assert_eq!(test, "[1,2]");
         Ok(())
@@ -99,4 +231,182 @@ mod tests {
         assert_eq!(test, "[[1,4],[2,3]]");
         Ok(())
     }
-}
\ No newline at end of file
+
+    fn number(input: &str) -> Box<Node> {
+        Box::new(Node::from(input))
+    }
+
+    fn reduce_steps(input: &str) -> Vec<String> {
+        let mut node = Node::from(input);
+        let mut steps = vec![];
+
+        while node.reduce_step() {
+            steps.push(node.to_string());
+        }
+
+        steps
+    }
+
+    const HOMEWORK: [&str; 10] = [
+        "[[[0,[5,8]],[[1,7],[9,6]]],[[4,[1,2]],[[1,4],2]]]",
+        "[[[5,[2,8]],4],[5,[[9,9],0]]]",
+        "[6,[[[6,2],[5,6]],[[7,6],[4,7]]]]",
+        "[[[6,[0,7]],[0,9]],[4,[9,[9,0]]]]",
+        "[[[7,[6,4]],[3,[1,3]]],[[[5,5],1],9]]",
+        "[[6,[[7,3],[3,2]]],[[[3,8],[5,7]],4]]",
+        "[[[[5,4],[7,7]],8],[[8,3],8]]",
+        "[[9,3],[[9,9],[6,[4,9]]]]",
+        "[[2,[[7,7],7]],[[5,8],[[9,3],[0,2]]]]",
+        "[[[[5,2],5],[8,[3,7]]],[[5,[7,5]],[4,4]]]",
+    ];
+
+    #[test]
+    fn test_parse() {
+        for input in [
+            "[1,2]",
+            "[[1,2],3]",
+            "[9,[8,7]]",
+            "[[1,9],[8,5]]",
+            "[[[[1,2],[3,4]],[[5,6],[7,8]]],9]",
+            "[[[9,[3,8]],[[0,9],6]],[[[3,7],[4,9]],3]]",
+            "[[[[1,3],[5,3]],[[1,3],[8,7]]],[[[4,9],[6,9]],[[8,2],[7,3]]]]",
+        ] {
+            assert_eq!(Node::from(input).to_string(), input);
+        }
+
+        assert_eq!(Node::from("[12,3]"), Node::Inner(Box::new(Node::Leaf(12)), Box::new(Node::Leaf(3))));
+    }
+
+    #[test]
+    fn test_add_pairs() {
+        assert_eq!((number("[1,2]") + number("[[3,4],5]")).to_string(), "[[1,2],[[3,4],5]]");
+    }
+
+    #[test]
+    fn test_explode() {
+        for (input, expected) in [
+            ("[[[[[9,8],1],2],3],4]", "[[[[0,9],2],3],4]"),
+            ("[7,[6,[5,[4,[3,2]]]]]", "[7,[6,[5,[7,0]]]]"),
+            ("[[6,[5,[4,[3,2]]]],1]", "[[6,[5,[7,0]]],3]"),
+            ("[[3,[2,[1,[7,3]]]],[6,[5,[4,[3,2]]]]]", "[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]"),
+            ("[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]", "[[3,[2,[8,0]]],[9,[5,[7,0]]]]"),
+        ] {
+            let mut node = Node::from(input);
+
+            assert!(node.explode());
+            assert_eq!(node.to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn test_split() {
+        for (value, expected) in [(10, "[5,5]"), (11, "[5,6]"), (12, "[6,6]")] {
+            let mut node = Node::Leaf(value);
+
+            assert!(node.split());
+            assert_eq!(node.to_string(), expected);
+        }
+
+        assert!(!Node::from("[9,[1,9]]").split());
+    }
+
+    #[test]
+    fn test_reduce() {
+        assert_eq!(
+            reduce_steps("[[[[[4,3],4],4],[7,[[8,4],9]]],[1,1]]"),
+            [
+                "[[[[0,7],4],[7,[[8,4],9]]],[1,1]]",
+                "[[[[0,7],4],[15,[0,13]]],[1,1]]",
+                "[[[[0,7],4],[[7,8],[0,13]]],[1,1]]",
+                "[[[[0,7],4],[[7,8],[0,[6,7]]]],[1,1]]",
+                "[[[[0,7],4],[[7,8],[6,0]]],[8,1]]",
+            ]
+        );
+
+        let add = number("[[[[4,3],4],4],[7,[[8,4],9]]]") + number("[1,1]");
+        assert_eq!(add.to_string(), "[[[[0,7],4],[[7,8],[6,0]]],[8,1]]");
+    }
+
+    #[test]
+    fn test_sum_lists() {
+        for (count, expected) in [
+            (4, "[[[[1,1],[2,2]],[3,3]],[4,4]]"),
+            (5, "[[[[3,0],[5,3]],[4,4]],[5,5]]"),
+            (6, "[[[[5,0],[7,4]],[5,5]],[6,6]]"),
+        ] {
+            let total = sum((1..=count).map(|i| number(&format!("[{},{}]", i, i)))).unwrap();
+
+            assert_eq!(total.to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn test_larger_sum() {
+        let numbers = [
+            "[[[0,[4,5]],[0,0]],[[[4,5],[2,6]],[9,5]]]",
+            "[7,[[[3,7],[4,3]],[[6,3],[8,8]]]]",
+            "[[2,[[0,8],[3,4]]],[[[6,7],1],[7,[1,6]]]]",
+            "[[[[2,4],7],[6,[0,5]]],[[[6,8],[2,8]],[[2,1],[4,5]]]]",
+            "[7,[5,[[3,8],[1,4]]]]",
+            "[[2,[2,2]],[8,[8,1]]]",
+            "[2,9]",
+            "[1,[[[9,3],9],[[9,0],[0,7]]]]",
+            "[[[5,[7,4]],7],1]",
+            "[[[[4,2],2],6],[8,7]]",
+        ];
+        let partial_sums = [
+            "[[[[4,0],[5,4]],[[7,7],[6,0]]],[[8,[7,7]],[[7,9],[5,0]]]]",
+            "[[[[6,7],[6,7]],[[7,7],[0,7]]],[[[8,7],[7,7]],[[8,8],[8,0]]]]",
+            "[[[[7,0],[7,7]],[[7,7],[7,8]]],[[[7,7],[8,8]],[[7,7],[8,7]]]]",
+            "[[[[7,7],[7,8]],[[9,5],[8,7]]],[[[6,8],[0,8]],[[9,9],[9,0]]]]",
+            "[[[[6,6],[6,6]],[[6,0],[6,7]]],[[[7,7],[8,9]],[8,[8,1]]]]",
+            "[[[[6,6],[7,7]],[[0,7],[7,7]]],[[[5,5],[5,6]],9]]",
+            "[[[[7,8],[6,7]],[[6,8],[0,8]]],[[[7,7],[5,0]],[[5,5],[5,6]]]]",
+            "[[[[7,7],[7,7]],[[8,7],[8,7]]],[[[7,0],[7,7]],9]]",
+            "[[[[8,7],[7,7]],[[8,6],[7,7]]],[[[0,7],[6,6]],[8,7]]]",
+        ];
+
+        let mut total = number(numbers[0]);
+        for (input, expected) in numbers[1..].iter().zip(partial_sums) {
+            total = total + number(input);
+
+            assert_eq!(total.to_string(), expected);
+        }
+
+        assert_eq!(sum(numbers.iter().map(|n| number(n))), Some(total));
+    }
+
+    #[test]
+    fn test_magnitude() {
+        for (input, expected) in [
+            ("[9,1]", 29),
+            ("[1,9]", 21),
+            ("[[9,1],[1,9]]", 129),
+            ("[[1,2],[[3,4],5]]", 143),
+            ("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]", 1384),
+            ("[[[[1,1],[2,2]],[3,3]],[4,4]]", 445),
+            ("[[[[3,0],[5,3]],[4,4]],[5,5]]", 791),
+            ("[[[[5,0],[7,4]],[5,5]],[6,6]]", 1137),
+            ("[[[[8,7],[7,7]],[[8,6],[7,7]]],[[[0,7],[6,6]],[8,7]]]", 3488),
+        ] {
+            assert_eq!(Node::from(input).magnitude(), expected);
+        }
+    }
+
+    #[test]
+    fn test_homework() {
+        let total = sum(HOMEWORK.iter().map(|n| number(n))).unwrap();
+
+        assert_eq!(total.to_string(), "[[[[6,6],[7,6]],[[7,7],[7,0]]],[[[7,7],[7,7]],[[7,8],[9,9]]]]");
+        assert_eq!(total.magnitude(), 4140);
+    }
+
+    #[test]
+    fn test_largest_magnitude() {
+        let numbers: Vec<_> = HOMEWORK.iter().map(|n| number(n)).collect();
+
+        let best = number(HOMEWORK[8]) + number(HOMEWORK[0]);
+        assert_eq!(best.to_string(), "[[[[7,8],[6,6]],[[6,0],[7,7]]],[[[7,8],[8,8]],[[7,9],[0,6]]]]");
+        assert_eq!(largest_magnitude(&numbers), 3993);
+    }
+}
@@ -1,5 +1,6 @@
 pub mod bingo;
 pub mod coord;
+pub mod dot;
 
 use std::fs::File;
 use std::io::{BufRead, BufReader};
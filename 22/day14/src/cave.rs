@@ -1,37 +1,53 @@
-use std::{collections::HashMap, fmt::Display};
+use std::fmt::Display;
 
 use utils_22::Coord;
 
+use crate::{
+    config::CaveConfig,
+    storage::{CellStorage, DenseCells, SparseCells},
+};
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
-enum Cell {
+pub enum Cell {
     Empty,
     Wall,
     RestingSand,
 }
 
 #[derive(Debug, Clone)]
-pub struct Cave {
+pub struct Cave<S = SparseCells> {
     min: Coord,
     max: Coord,
-    cells: HashMap<Coord, Cell>,
-    floor: bool,
+    cells: S,
+    config: CaveConfig,
+    paths: Vec<Vec<Coord>>,
+    next_source: usize,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DropResult {
     Resting(Coord),
     Overflow,
+    Blocked,
 }
 
 impl From<&str> for Cave {
     fn from(input: &str) -> Self {
+        Cave::with_config(input, CaveConfig::default())
+    }
+}
+
+impl Cave {
+    pub fn with_floor(input: &str) -> Self {
+        Cave::with_config(input, CaveConfig::with_floor())
+    }
+
+    pub fn with_config(input: &str, config: CaveConfig) -> Self {
+        config.validate();
+
         let shapes: Vec<_> = input
             .lines()
-            .map(|line| {
-                line.split(" -> ")
-                    .map(|str| Coord::from(str))
-                    .collect::<Vec<_>>()
-            })
+            .map(|line| line.split(" -> ").map(Coord::from).collect::<Vec<_>>())
             .collect();
 
         let min = shapes
@@ -39,12 +55,17 @@ impl From<&str> for Cave {
             .flatten()
             .fold(Coord::new(i32::MAX, i32::MAX), |acc, el| acc.min(el));
 
-        let max = shapes
+        let mut max = shapes
             .iter()
             .flatten()
             .fold(Coord::new(0, 0), |acc, el| acc.max(el));
 
-        let mut cells: HashMap<_, _> = HashMap::new();
+        if let Some(offset) = config.floor_offset {
+            // Sand rests on the row above the floor
+            max = &max + &Coord::new(0, offset - 1);
+        }
+
+        let mut cells = SparseCells::new();
 
         for coords in shapes {
             for pair in coords.windows(2) {
@@ -61,27 +82,43 @@ impl From<&str> for Cave {
             min,
             max,
             cells,
-            floor: false,
+            paths: vec![vec![]; config.sources.len()],
+            config,
+            next_source: 0,
         }
     }
-}
 
-impl Cave {
-    pub fn with_floor(input: &str) -> Self {
-        let mut cave: Cave = input.into();
+    /// Copies the cave into a fixed size grid that is large enough for every grain that can come to rest.
+    pub fn into_dense(self) -> Cave<DenseCells> {
+        let (top_left, bottom_right) = self.extent();
+        // Sand can pile up at most as far sideways as the cave is deep
+        let spread = self.config.max_spread() * (self.max.y() - top_left.y() + 1) + 1;
+
+        let mut cells = DenseCells::new(
+            Coord::new(top_left.x() - spread, top_left.y()),
+            Coord::new(bottom_right.x() + spread, bottom_right.y()),
+        );
 
-        cave.max = &cave.max + &Coord::new(0, 1);
+        for (coord, cell) in self.cells {
+            cells.set(coord, cell);
+        }
 
-        cave.floor = true;
-        cave
+        Cave {
+            min: self.min,
+            max: self.max,
+            cells,
+            config: self.config,
+            paths: self.paths,
+            next_source: self.next_source,
+        }
     }
 }
 
-impl Display for Cave {
+impl<S: CellStorage> Display for Cave<S> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         for y in self.min.y()..=self.max.y() {
             for x in self.min.x()..=self.max.x() {
-                let cell = self.cells.get(&Coord::new(x, y)).unwrap_or(&Cell::Empty);
+                let cell = self.cells.get(&Coord::new(x, y));
                 write!(
                     f,
                     "{}",
@@ -99,43 +136,143 @@ impl Display for Cave {
     }
 }
 
-impl Cave {
-    fn get(&self, coord: &Coord) -> &Cell {
-        self.cells.get(coord).unwrap_or(&Cell::Empty)
+impl<S: CellStorage> Cave<S> {
+    fn get(&self, coord: &Coord) -> Cell {
+        self.cells.get(coord)
+    }
+
+    fn extent(&self) -> (Coord, Coord) {
+        self.config
+            .sources
+            .iter()
+            .fold((self.min, self.max), |(min, max), source| {
+                (min.min(source), max.max(source))
+            })
     }
 
+    /// Drops a single grain from the next source in turn.
     pub fn drop_sand(&mut self) -> DropResult {
-        let mut current_pos = Coord::new(500, 0);
+        let source = self.next_source;
+        self.next_source = (self.next_source + 1) % self.config.sources.len();
+
+        self.drop_sand_from(source)
+    }
+
+    /// Drops a single grain. The grain starts where the path of the previous one diverged instead of at the source.
+    pub fn drop_sand_from(&mut self, source: usize) -> DropResult {
+        if self.paths[source].is_empty() {
+            let start = self.config.sources[source];
+
+            if self.get(&start) != Cell::Empty {
+                return DropResult::Blocked;
+            }
+
+            self.paths[source].push(start);
+        }
+
+        let mut current_pos = *self.paths[source].last().unwrap();
 
         while current_pos.y() < self.max.y() {
-            // println!("{current_pos}");
-            let next_posses = [
-                &current_pos + &Coord::UP,
-                &(&current_pos + &Coord::UP) + &Coord::LEFT,
-                &(&current_pos + &Coord::UP) + &Coord::RIGHT,
-            ];
-
-            let next_empty = next_posses
+            let next_empty = self
+                .config
+                .directions
                 .iter()
-                .find(|coord| matches!(self.get(coord), Cell::Empty));
+                .map(|dir| &current_pos + dir)
+                .find(|coord| matches!(self.get(coord), Cell::Empty))
+                .or_else(|| self.config.flow.then(|| self.slide(&current_pos)).flatten());
 
             match next_empty {
-                Some(coord) => current_pos = *coord,
+                Some(coord) => {
+                    current_pos = coord;
+                    self.paths[source].push(coord);
+                }
                 None => {
                     // Found no next spot => Resting Sand and return
-                    self.cells.insert(current_pos, Cell::RestingSand);
-                    return DropResult::Resting(current_pos);
+                    return self.rest(source, current_pos);
                 }
             }
         }
 
-        if self.floor {
-            self.cells.insert(current_pos, Cell::RestingSand);
-            DropResult::Resting(current_pos)
+        if self.config.floor_offset.is_some() {
+            self.rest(source, current_pos)
         } else {
             DropResult::Overflow
         }
     }
+
+    fn can_fall(&self, coord: &Coord) -> bool {
+        self.config
+            .directions
+            .iter()
+            .any(|dir| self.get(&(coord + dir)) == Cell::Empty)
+    }
+
+    /// Finds the nearest empty cell in the same row from which the grain can fall, preferring the left side on ties.
+    fn slide(&self, coord: &Coord) -> Option<Coord> {
+        let (top_left, bottom_right) = self.extent();
+        let mut open = [true, true];
+
+        for distance in 1.. {
+            for (side, offset) in [-distance, distance].into_iter().enumerate() {
+                if !open[side] {
+                    continue;
+                }
+
+                let candidate = Coord::new(coord.x() + offset, coord.y());
+
+                if self.get(&candidate) != Cell::Empty
+                    || candidate.x() < top_left.x() - 1
+                    || candidate.x() > bottom_right.x() + 1
+                {
+                    open[side] = false;
+                } else if self.can_fall(&candidate) {
+                    return Some(candidate);
+                }
+            }
+
+            if open == [false, false] {
+                break;
+            }
+        }
+
+        None
+    }
+
+    fn rest(&mut self, source: usize, pos: Coord) -> DropResult {
+        self.paths[source].pop();
+        self.cells.set(pos, Cell::RestingSand);
+
+        if self.config.flow {
+            // A slide depends on the whole row, so a remembered path can not be trusted anymore
+            self.paths.iter_mut().for_each(Vec::clear);
+        } else if self.paths.len() > 1 {
+            for path in &mut self.paths {
+                if let Some(index) = path.iter().position(|coord| *coord == pos) {
+                    path.truncate(index);
+                }
+            }
+        }
+
+        DropResult::Resting(pos)
+    }
+
+    /// Drops grains until every source overflows or is blocked and returns the number of resting grains.
+    pub fn fill_until_blocked(&mut self) -> usize {
+        let mut grains = 0;
+        let mut idle_sources = 0;
+
+        while idle_sources < self.config.sources.len() {
+            match self.drop_sand() {
+                DropResult::Resting(_) => {
+                    grains += 1;
+                    idle_sources = 0;
+                }
+                DropResult::Overflow | DropResult::Blocked => idle_sources += 1,
+            }
+        }
+
+        grains
+    }
 }
 
 #[cfg(test)]
@@ -144,6 +281,7 @@ mod tests {
     use utils_22::Coord;
 
     use super::{Cave, DropResult};
+    use crate::config::CaveConfig;
 
     #[test]
     fn test_display() {
@@ -232,11 +370,116 @@ mod tests {
                         Some(pos)
                     }
                 }
-                DropResult::Overflow => None,
+                DropResult::Overflow | DropResult::Blocked => None,
             })
             .count()
             + 1;
 
         assert_eq!(grains, 93)
     }
+
+    #[test]
+    fn fill_until_blocked() {
+        let input = include_str!("test");
+
+        assert_eq!(Cave::from(input).fill_until_blocked(), 24);
+        assert_eq!(Cave::with_floor(input).fill_until_blocked(), 93);
+    }
+
+    #[test]
+    fn blocked_source() {
+        let input = include_str!("test");
+        let mut cave = Cave::with_floor(input);
+
+        cave.fill_until_blocked();
+
+        assert_eq!(cave.drop_sand(), DropResult::Blocked);
+    }
+
+    #[test]
+    fn dense_matches_sparse() {
+        let input = include_str!("test");
+
+        for (mut sparse, mut dense) in [
+            (Cave::from(input), Cave::from(input).into_dense()),
+            (
+                Cave::with_floor(input),
+                Cave::with_floor(input).into_dense(),
+            ),
+        ] {
+            loop {
+                let result = sparse.drop_sand();
+                assert_eq!(dense.drop_sand(), result);
+
+                if !matches!(result, DropResult::Resting(_)) {
+                    break;
+                }
+            }
+
+            assert_eq!(format!("{sparse}"), format!("{dense}"));
+        }
+    }
+
+    #[test]
+    fn two_sources() {
+        let input = include_str!("test");
+        let config = CaveConfig {
+            sources: vec![Coord::new(500, 0), Coord::new(496, 0)],
+            ..CaveConfig::with_floor()
+        };
+
+        let mut sparse = Cave::with_config(input, config.clone());
+        let mut dense = Cave::with_config(input, config).into_dense();
+
+        assert_eq!(sparse.fill_until_blocked(), dense.fill_until_blocked());
+        assert_eq!(format!("{sparse}"), format!("{dense}"));
+        assert_eq!(sparse.drop_sand(), DropResult::Blocked);
+        assert_eq!(sparse.drop_sand(), DropResult::Blocked);
+    }
+
+    #[test]
+    fn floor_offset() {
+        let input = include_str!("test");
+        let config = CaveConfig {
+            floor_offset: Some(1),
+            ..Default::default()
+        };
+        let mut cave = Cave::with_config(input, config);
+
+        assert_eq!(cave.drop_sand(), DropResult::Resting(Coord::new(500, 8)));
+        assert_eq!(cave.fill_until_blocked(), 78);
+    }
+
+    #[test]
+    fn straight_down() {
+        let input = "498,2 -> 502,2";
+        let config = CaveConfig {
+            directions: vec![Coord::UP],
+            ..Default::default()
+        };
+        let mut cave = Cave::with_config(input, config);
+
+        assert_eq!(cave.fill_until_blocked(), 2);
+    }
+
+    #[test]
+    fn water_fills_basin() {
+        const EXPECTED: &str = "#+++#
+#+++#
+#+++#
+#####
+";
+        let input = "498,1 -> 498,4 -> 502,4 -> 502,1";
+        let mut cave = Cave::with_config(
+            input,
+            CaveConfig {
+                directions: vec![Coord::UP],
+                flow: true,
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(cave.fill_until_blocked(), 9);
+        assert_eq!(format!("{cave}"), EXPECTED);
+    }
 }
@@ -0,0 +1,53 @@
+use utils_22::Coord;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaveConfig {
+    /// Points where grains enter the cave. Grains are dropped from them in turn.
+    pub sources: Vec<Coord>,
+    /// Distance of the floor below the lowest wall or `None` if sand falls into the abyss.
+    pub floor_offset: Option<i32>,
+    /// Moves a grain tries in order. Every move has to go down.
+    pub directions: Vec<Coord>,
+    /// Grains that can not fall slide sideways like water to the nearest spot where they can fall again.
+    pub flow: bool,
+}
+
+impl Default for CaveConfig {
+    fn default() -> Self {
+        CaveConfig {
+            sources: vec![Coord::new(500, 0)],
+            floor_offset: None,
+            directions: vec![Coord::UP, Coord::UP_LEFT, Coord::UP_RIGHT],
+            flow: false,
+        }
+    }
+}
+
+impl CaveConfig {
+    pub fn with_floor() -> Self {
+        CaveConfig {
+            floor_offset: Some(2),
+            ..Default::default()
+        }
+    }
+
+    pub fn validate(&self) {
+        assert!(!self.sources.is_empty(), "need at least one source");
+        assert!(
+            !self.directions.is_empty() && self.directions.iter().all(|dir| dir.y() > 0),
+            "every direction has to go down"
+        );
+        assert!(
+            self.floor_offset.is_none_or(|offset| offset > 0),
+            "the floor has to be below the lowest wall"
+        );
+    }
+
+    pub(crate) fn max_spread(&self) -> i32 {
+        self.directions
+            .iter()
+            .map(|dir| dir.x().abs())
+            .max()
+            .unwrap_or(0)
+    }
+}
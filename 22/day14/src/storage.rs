@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+
+use utils_22::Coord;
+
+use crate::cave::Cell;
+
+pub trait CellStorage {
+    fn get(&self, coord: &Coord) -> Cell;
+    fn set(&mut self, coord: Coord, cell: Cell);
+}
+
+pub type SparseCells = HashMap<Coord, Cell>;
+
+impl CellStorage for SparseCells {
+    fn get(&self, coord: &Coord) -> Cell {
+        self.get(coord).copied().unwrap_or(Cell::Empty)
+    }
+
+    fn set(&mut self, coord: Coord, cell: Cell) {
+        self.insert(coord, cell);
+    }
+}
+
+/// Fixed size grid covering `min..=max`. Reads outside of the bounds are empty, writes panic.
+#[derive(Debug, Clone)]
+pub struct DenseCells {
+    min: Coord,
+    width: usize,
+    height: usize,
+    cells: Vec<Cell>,
+}
+
+impl DenseCells {
+    pub fn new(min: Coord, max: Coord) -> Self {
+        assert!(min.x() <= max.x() && min.y() <= max.y());
+
+        let width = (max.x() - min.x() + 1) as usize;
+        let height = (max.y() - min.y() + 1) as usize;
+
+        DenseCells {
+            min,
+            width,
+            height,
+            cells: vec![Cell::Empty; width * height],
+        }
+    }
+
+    fn index(&self, coord: &Coord) -> Option<usize> {
+        let local = coord - &self.min;
+
+        if local.x() < 0 || local.y() < 0 {
+            return None;
+        }
+
+        let (x, y) = (local.x() as usize, local.y() as usize);
+
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        Some(y * self.width + x)
+    }
+}
+
+impl CellStorage for DenseCells {
+    fn get(&self, coord: &Coord) -> Cell {
+        self.index(coord)
+            .map_or(Cell::Empty, |index| self.cells[index])
+    }
+
+    fn set(&mut self, coord: Coord, cell: Cell) {
+        let index = self
+            .index(&coord)
+            .unwrap_or_else(|| panic!("{coord} is outside of the dense grid"));
+
+        self.cells[index] = cell;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use utils_22::Coord;
+
+    use super::{CellStorage, DenseCells};
+    use crate::cave::Cell;
+
+    #[test]
+    fn dense_out_of_bounds_is_empty() {
+        let mut cells = DenseCells::new(Coord::new(-1, 0), Coord::new(1, 2));
+
+        cells.set(Coord::new(-1, 2), Cell::Wall);
+
+        assert_eq!(cells.get(&Coord::new(-1, 2)), Cell::Wall);
+        assert_eq!(cells.get(&Coord::new(-2, 2)), Cell::Empty);
+        assert_eq!(cells.get(&Coord::new(0, 3)), Cell::Empty);
+    }
+
+    #[test]
+    #[should_panic]
+    fn dense_write_out_of_bounds_panics() {
+        let mut cells = DenseCells::new(Coord::ZERO, Coord::new(1, 1));
+
+        cells.set(Coord::new(2, 0), Cell::Wall);
+    }
+}
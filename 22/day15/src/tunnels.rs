@@ -11,44 +11,136 @@ pub struct Tunnels {
     pub sensors: Vec<Sensor>,
 }
 
+impl Sensor {
+    fn covers(&self, coord: &Coord) -> bool {
+        self.coord.manhattan(coord) <= self.manhattan
+    }
+}
+
 impl Tunnels {
-    pub fn find_distress_beacon(&self) -> Option<Coord> {
-        let sensors = &self.sensors;
-
-        const ROWS: i32 = 4000000;
-
-        for row in 0..=ROWS {
-            let mut ranges = sensors
-                .iter()
-                .filter_map(|sensor| {
-                    let delta_row = (row - sensor.coord.y()).abs();
-                    let width = sensor.manhattan as i32 - delta_row;
-                    if width < 0 {
-                        return None;
-                    }
+    /// Merged and sorted ranges of x positions in row `y` that are covered by at least one sensor.
+    pub fn covered_ranges_in_row(&self, y: i32) -> Vec<(i32, i32)> {
+        let mut ranges = self
+            .sensors
+            .iter()
+            .filter_map(|sensor| {
+                let delta_row = (y - sensor.coord.y()).abs();
+                let width = sensor.manhattan as i32 - delta_row;
+                if width < 0 {
+                    return None;
+                }
+
+                Some((sensor.coord.x() - width, sensor.coord.x() + width))
+            })
+            .collect::<Vec<_>>();
+
+        ranges.sort_unstable();
+
+        let mut merged: Vec<(i32, i32)> = Vec::with_capacity(ranges.len());
+
+        for (begin, end) in ranges {
+            match merged.last_mut() {
+                Some((_, last_end)) if begin <= *last_end + 1 => *last_end = end.max(*last_end),
+                _ => merged.push((begin, end)),
+            }
+        }
+
+        merged
+    }
+
+    /// Number of positions in row `y` where no beacon can be present.
+    pub fn covered_positions_in_row(&self, y: i32) -> usize {
+        let ranges = self.covered_ranges_in_row(y);
+
+        let covered: usize = ranges
+            .iter()
+            .map(|(begin, end)| (end - begin + 1) as usize)
+            .sum();
+
+        let mut beacons: Vec<_> = self
+            .sensors
+            .iter()
+            .map(|sensor| sensor.closest_beacon)
+            .filter(|beacon| beacon.y() == y)
+            .collect();
+        beacons.sort_unstable_by_key(|beacon| beacon.x());
+        beacons.dedup();
+
+        covered - beacons.len()
+    }
+
+    /// Searches the distress beacon in the square `0..=bound`.
+    ///
+    /// The beacon has to be next to the border of at least two sensors, so only the intersections of the
+    /// diagonal lines just outside of each sensor's range are checked. Falls back to scanning every row
+    /// if the beacon sits in a corner of the search area where this does not hold.
+    pub fn find_distress_beacon(&self, bound: i32) -> Option<Coord> {
+        self.find_distress_beacon_by_boundaries(bound)
+            .or_else(|| self.find_distress_beacon_by_rows(bound))
+    }
+
+    fn is_uncovered(&self, coord: &Coord, bound: i32) -> bool {
+        (0..=bound).contains(&coord.x())
+            && (0..=bound).contains(&coord.y())
+            && self.sensors.iter().all(|sensor| !sensor.covers(coord))
+    }
+
+    pub fn find_distress_beacon_by_boundaries(&self, bound: i32) -> Option<Coord> {
+        // Lines x + y = c rising to the right and x - y = c falling to the right
+        let mut rising = Vec::with_capacity(self.sensors.len() * 2);
+        let mut falling = Vec::with_capacity(self.sensors.len() * 2);
+
+        for sensor in &self.sensors {
+            let (x, y) = (sensor.coord.x() as i64, sensor.coord.y() as i64);
+            let distance = sensor.manhattan as i64 + 1;
+
+            rising.extend([x + y - distance, x + y + distance]);
+            falling.extend([x - y - distance, x - y + distance]);
+        }
+
+        rising.sort_unstable();
+        rising.dedup();
+        falling.sort_unstable();
+        falling.dedup();
+
+        for a in &rising {
+            for b in &falling {
+                if (a + b) % 2 != 0 {
+                    continue;
+                }
 
-                    Some((sensor.coord.x() - width, sensor.coord.x() + width))
-                })
-                .collect::<Vec<_>>();
+                let (x, y) = ((a + b) / 2, (a - b) / 2);
 
-            ranges.sort_by(|(a_min, a_max), (b_min, b_max)| {
-                a_min.cmp(&b_min).then(a_max.cmp(&b_max))
-            });
+                if x < 0 || y < 0 || x > bound as i64 || y > bound as i64 {
+                    continue;
+                }
+
+                let candidate = Coord::new(x as i32, y as i32);
+
+                if self.is_uncovered(&candidate, bound) {
+                    return Some(candidate);
+                }
+            }
+        }
 
-            // #[cfg(test)]
-            // if row == 11 {
-            //     println!("{:#?}", ranges);
-            // }
+        None
+    }
 
+    pub fn find_distress_beacon_by_rows(&self, bound: i32) -> Option<Coord> {
+        for row in 0..=bound {
             let mut invalid_x = 0;
 
-            for (begin, end) in &ranges {
-                if begin > &invalid_x {
-                    return Some(Coord::new(invalid_x, row));
+            for (begin, end) in self.covered_ranges_in_row(row) {
+                if begin > invalid_x {
+                    break;
                 }
 
                 invalid_x = (end + 1).max(invalid_x);
             }
+
+            if invalid_x <= bound {
+                return Some(Coord::new(invalid_x, row));
+            }
         }
 
         None
@@ -113,40 +205,35 @@ mod tests {
     #[test]
     fn test_data() {
         let tunnels = Tunnels::from(TEST);
-        let sensors = &tunnels.sensors;
-        let min_x = sensors
-            .iter()
-            .map(|s| s.coord.x() - s.manhattan as i32)
-            .min()
-            .unwrap();
-        let max_x = tunnels
-            .sensors
-            .iter()
-            .map(|s| s.coord.x() + s.manhattan as i32)
-            .max()
-            .unwrap();
-
-        let mut posses = Vec::new();
-        for coord in (min_x..=max_x).map(|x| Coord::new(x, 10)) {
-            for sensor in sensors {
-                if coord.manhattan(&sensor.coord) <= sensor.manhattan {
-                    posses.push(coord);
-                    break;
-                }
-            }
-        }
 
-        posses.retain(|pos| sensors.iter().map(|s| s.closest_beacon).all(|b| pos != &b));
-
-        assert_eq!(posses.len(), 26);
+        assert_eq!(tunnels.covered_positions_in_row(10), 26);
     }
 
     #[test]
     fn test_distress_beacon() {
         let tunnels = Tunnels::from(TEST);
 
-        let pos = tunnels.find_distress_beacon();
+        let pos = tunnels.find_distress_beacon(20);
 
         assert_eq!(pos, Some(Coord::new(14, 11)));
     }
+
+    #[test]
+    fn search_methods_agree() {
+        let tunnels = Tunnels::from(TEST);
+
+        assert_eq!(
+            tunnels.find_distress_beacon_by_boundaries(20),
+            tunnels.find_distress_beacon_by_rows(20)
+        );
+    }
+
+    #[test]
+    fn corner_falls_back_to_rows() {
+        // A single sensor covering everything but the corner (0, 0)
+        let tunnels = Tunnels::from("Sensor at x=2, y=2: closest beacon is at x=2, y=5");
+
+        assert_eq!(tunnels.find_distress_beacon_by_boundaries(2), None);
+        assert_eq!(tunnels.find_distress_beacon(2), Some(Coord::new(0, 0)));
+    }
 }
@@ -1,20 +1,26 @@
+use std::env;
+
 use network::Network;
 
-use crate::{elephant_solver::find_elephant_order, solo_solver::find_solo_order};
+use crate::solver::{find_elephant_order, find_solo_order};
 
-mod elephant_solver;
 mod network;
-mod solo_solver;
+mod plan;
+mod solver;
 
 const INPUT: &str = include_str!("input");
 fn main() {
     let network = Network::from(INPUT);
 
-    let max = find_solo_order(&network, "AA".into());
+    let plan = find_solo_order(&network, "AA");
 
-    println!("solo: {max}");
+    // `cargo run -- replay` walks through the solo plan minute by minute
+    if env::args().nth(1).is_some_and(|arg| arg == "replay") {
+        print!("{}", plan.replay(&network));
+    }
+    println!("solo: {}", plan.released);
 
-    let max = find_elephant_order(&network, "AA".into());
+    let plan = find_elephant_order(&network, "AA");
 
-    println!("ele: {max}");
+    println!("ele: {}", plan.released);
 }
@@ -1,4 +1,6 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
+
+use utils_22::{bfs, DotGraph, ToDot};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Node {
@@ -11,18 +13,14 @@ pub struct Network {
     pub nodes: HashMap<String, Node>,
 }
 
+/// Graph reduced to the valves with a positive flow rate plus the start.
+///
+/// Valves get the ids `0..valve_count()` ordered by name, the start is always the last id.
 #[derive(Debug, Clone, PartialEq, Eq)]
-struct Entry(String, u32);
-
-impl Ord for Entry {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.1.partial_cmp(&other.1).unwrap()
-    }
-}
-impl PartialOrd for Entry {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.1.partial_cmp(&other.1)
-    }
+pub struct CompressedNetwork {
+    pub names: Vec<String>,
+    pub rates: Vec<u32>,
+    distances: Vec<u32>,
 }
 
 impl From<&str> for Network {
@@ -55,99 +53,133 @@ impl From<&str> for Network {
     }
 }
 
-pub type PathsType = HashMap<String, HashMap<String, u32>>;
-
 impl Network {
-    fn shortest_paths(&self, origin: String) -> HashMap<String, Option<String>> {
-        let mut distances = vec![];
-        let mut previous = HashMap::new();
-        let mut q = HashSet::new();
-        for (name, _) in &self.nodes {
-            let distance = if name == &origin { 0 } else { u32::MAX };
-            distances.push(Entry(name.clone(), distance));
-            previous.insert(name.clone(), None::<String>);
-            q.insert(name.clone());
-        }
+    /// Computes all walking distances with Floyd-Warshall and keeps the ones between valves and the start.
+    pub fn compress(&self, start: &str) -> CompressedNetwork {
+        let mut all_names: Vec<_> = self.nodes.keys().map(String::as_str).collect();
+        all_names.sort_unstable();
 
-        distances.sort();
+        let id = |name: &str| all_names.binary_search(&name).unwrap();
 
-        while !q.is_empty() {
-            let u = distances.iter().find(|e| q.contains(&e.0)).unwrap().clone();
+        let count = all_names.len();
+        let mut distances = vec![u32::MAX; count * count];
 
-            q.remove(&u.0);
+        for (from, name) in all_names.iter().enumerate() {
+            distances[from * count + from] = 0;
 
-            let neighbours = &self.nodes.get(&u.0).unwrap().neighbours;
-            for neighbour in neighbours {
-                if q.contains(neighbour) {
-                    let neighbour_index = distances.iter().position(|e| &e.0 == neighbour).unwrap();
-                    let mut neighbour_entry = distances.get_mut(neighbour_index).unwrap();
-                    let new_dist = u.1 + 1;
-                    if new_dist < neighbour_entry.1 {
-                        neighbour_entry.1 = new_dist;
-                        *previous.get_mut(neighbour).unwrap() = Some(u.0.clone());
-                        distances.sort();
-                    }
-                }
+            for neighbour in &self.nodes[*name].neighbours {
+                distances[from * count + id(neighbour)] = 1;
             }
         }
 
-        previous
-    }
-
-    pub fn get_paths_to_valves_from(&self, origin: String) -> Vec<Vec<String>> {
-        let previous = self.shortest_paths(origin.clone());
+        for via in 0..count {
+            for from in 0..count {
+                let to_via = distances[from * count + via];
 
-        self.nodes
-            .iter()
-            .filter_map(|(name, node)| {
-                if node.rate == 0 {
-                    return None;
+                if to_via == u32::MAX {
+                    continue;
                 }
 
-                let mut path = vec![name.clone()];
-                let mut current = name;
+                for to in 0..count {
+                    let from_via = distances[via * count + to];
 
-                while let Some(prev) = previous.get(current).unwrap() {
-                    path.push(prev.clone());
-                    current = prev;
+                    if from_via != u32::MAX && to_via + from_via < distances[from * count + to] {
+                        distances[from * count + to] = to_via + from_via;
+                    }
                 }
-                path.reverse();
-                Some(path)
-            })
-            .collect()
-    }
-    pub fn get_valve_nodes_and_paths(&self, start: &String) -> (HashSet<String>, PathsType) {
-        let valve_nodes: HashSet<_> = self
-            .nodes
+            }
+        }
+
+        let names: Vec<String> = all_names
             .iter()
-            .filter_map(|(name, node)| {
-                if node.rate == 0 {
-                    None
-                } else {
-                    Some(name.clone())
-                }
-            })
+            .filter(|name| self.nodes[**name].rate > 0)
+            .chain([&start])
+            .map(|name| name.to_string())
             .collect();
-        let start_vec = vec![start.clone()];
-        let paths: PathsType = valve_nodes
+
+        let rates = names.iter().map(|name| self.nodes[name].rate).collect();
+
+        let kept: Vec<_> = names.iter().map(|name| id(name)).collect();
+        let distances = kept
             .iter()
-            .chain(start_vec.iter())
-            .map(|node| {
-                let paths = self.get_paths_to_valves_from((*node).clone());
-                let paths: HashMap<_, _> = paths
-                    .into_iter()
-                    .map(|entry| (entry[entry.len() - 1].clone(), entry.len() as u32))
-                    .collect();
-                ((*node).clone(), paths)
-            })
+            .flat_map(|from| kept.iter().map(|to| (*from, *to)))
+            .map(|(from, to)| distances[from * count + to])
             .collect();
 
-        (valve_nodes, paths)
+        CompressedNetwork {
+            names,
+            rates,
+            distances,
+        }
+    }
+}
+
+impl Network {
+    /// Shortest walk from `from` to `to` including both ends.
+    pub fn path(&self, from: &str, to: &str) -> Vec<String> {
+        bfs(
+            from,
+            |name| *name,
+            |name| self.nodes[*name].neighbours.iter().map(String::as_str),
+            |name| *name == to,
+        )
+        .unwrap_or_else(|| panic!("no path from {from} to {to}"))
+        .into_iter()
+        .map(String::from)
+        .collect()
+    }
+}
+
+impl CompressedNetwork {
+    pub fn valve_count(&self) -> usize {
+        self.names.len() - 1
+    }
+
+    pub fn start(&self) -> usize {
+        self.valve_count()
+    }
+
+    pub fn distance(&self, from: usize, to: usize) -> u32 {
+        self.distances[from * self.names.len() + to]
+    }
+}
+
+impl ToDot for Network {
+    fn to_dot(&self) -> DotGraph {
+        let mut graph = DotGraph::new("valves", false);
+
+        let mut names: Vec<_> = self.nodes.keys().collect();
+        names.sort_unstable();
+
+        for name in &names {
+            let rate = self.nodes[*name].rate;
+            let label = format!("{name}\nrate={rate}");
+
+            if rate == 0 {
+                graph.add_node(
+                    name,
+                    Some(&label),
+                    &[("color", "grey"), ("fontcolor", "grey")],
+                );
+            } else {
+                graph.add_node(name, Some(&label), &[]);
+            }
+        }
+
+        for name in names {
+            for neighbour in &self.nodes[name].neighbours {
+                graph.add_edge(name, neighbour, Some(1));
+            }
+        }
+
+        graph
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use utils_22::ToDot;
+
     use super::Network;
 
     const TEST: &str = include_str!("test");
@@ -155,4 +187,48 @@ mod tests {
     fn test_parsing() {
         let _network = Network::from(TEST);
     }
+
+    #[test]
+    fn test_compress() {
+        let network = Network::from(TEST).compress("AA");
+
+        assert_eq!(
+            network.names,
+            ["BB", "CC", "DD", "EE", "HH", "JJ", "AA"].map(String::from)
+        );
+        assert_eq!(network.rates, [13, 2, 20, 3, 22, 21, 0]);
+        assert_eq!(network.start(), 6);
+
+        // AA -> DD -> EE -> FF -> GG -> HH
+        assert_eq!(network.distance(6, 4), 5);
+        assert_eq!(network.distance(4, 6), 5);
+        assert_eq!(network.distance(5, 5), 0);
+    }
+
+    #[test]
+    fn test_path() {
+        let network = Network::from(TEST);
+
+        assert_eq!(
+            network.path("AA", "HH"),
+            ["AA", "DD", "EE", "FF", "GG", "HH"].map(String::from)
+        );
+        assert_eq!(network.path("AA", "AA"), ["AA"].map(String::from));
+    }
+
+    #[test]
+    fn test_dot() {
+        let network = Network::from(TEST);
+
+        let dot = network.to_dot_with_path(&["AA", "DD", "EE"]).to_string();
+
+        assert!(dot.starts_with("graph \"valves\" {\n"));
+        assert!(dot.contains(
+            "\"AA\" [label=\"AA\\nrate=0\", color=\"red\", fontcolor=\"grey\", penwidth=\"2\"];"
+        ));
+        assert!(dot.contains("\"BB\" [label=\"BB\\nrate=13\"];"));
+        assert!(dot.contains("\"AA\" -- \"DD\" [label=\"1\", color=\"red\", penwidth=\"2\"];"));
+        assert!(dot.contains("\"AA\" -- \"BB\" [label=\"1\"];"));
+        assert!(!dot.contains("\"BB\" -- \"AA\""));
+    }
 }
@@ -0,0 +1,81 @@
+use std::fmt::Write;
+
+use crate::network::Network;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Move,
+    Open,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    pub minute: u32,
+    pub valve: String,
+    pub action: Action,
+}
+
+/// Steps of every agent, the first agent is "you" and the second one the elephant.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plan {
+    pub released: u32,
+    pub time: u32,
+    pub agents: Vec<Vec<Step>>,
+}
+
+fn describe(agent: usize, step: &Step) -> String {
+    let (name, verb) = match (agent, step.action) {
+        (0, Action::Move) => ("You".to_string(), "move to"),
+        (0, Action::Open) => ("You".to_string(), "open"),
+        (1, Action::Move) => ("The elephant".to_string(), "moves to"),
+        (1, Action::Open) => ("The elephant".to_string(), "opens"),
+        (agent, Action::Move) => (format!("Elephant {agent}"), "moves to"),
+        (agent, Action::Open) => (format!("Elephant {agent}"), "opens"),
+    };
+
+    format!("{name} {verb} valve {}.", step.valve)
+}
+
+fn describe_open(open: &[&str], pressure: u32) -> String {
+    match open {
+        [] => "No valves are open.".to_string(),
+        [valve] => format!("Valve {valve} is open, releasing {pressure} pressure."),
+        [first, second] => {
+            format!("Valves {first} and {second} are open, releasing {pressure} pressure.")
+        }
+        [rest @ .., last] => format!(
+            "Valves {}, and {last} are open, releasing {pressure} pressure.",
+            rest.join(", ")
+        ),
+    }
+}
+
+impl Plan {
+    /// Minute by minute walkthrough in the format of the puzzle text.
+    pub fn replay(&self, network: &Network) -> String {
+        let mut output = String::new();
+        let mut open: Vec<&str> = vec![];
+
+        for minute in 1..=self.time {
+            let pressure = open.iter().map(|valve| network.nodes[*valve].rate).sum();
+
+            writeln!(output, "== Minute {minute} ==").unwrap();
+            writeln!(output, "{}", describe_open(&open, pressure)).unwrap();
+
+            for (agent, steps) in self.agents.iter().enumerate() {
+                for step in steps.iter().filter(|step| step.minute == minute) {
+                    writeln!(output, "{}", describe(agent, step)).unwrap();
+
+                    if step.action == Action::Open {
+                        open.push(&step.valve);
+                    }
+                }
+            }
+
+            open.sort_unstable();
+            writeln!(output).unwrap();
+        }
+
+        output
+    }
+}
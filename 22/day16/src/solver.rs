@@ -0,0 +1,345 @@
+use crate::{
+    network::{CompressedNetwork, Network},
+    plan::{Action, Plan, Step},
+};
+
+fn visit(
+    valves: &CompressedNetwork,
+    pos: usize,
+    time: u32,
+    opened: u64,
+    released: u32,
+    best: &mut [u32],
+) {
+    best[opened as usize] = best[opened as usize].max(released);
+
+    for next in 0..valves.valve_count() {
+        let bit = 1 << next;
+        // Walking there and opening the valve, valves that can not be reached stay at u32::MAX
+        let Some(cost) = valves.distance(pos, next).checked_add(1) else {
+            continue;
+        };
+
+        if opened & bit != 0 || cost >= time {
+            continue;
+        }
+
+        let remaining = time - cost;
+
+        visit(
+            valves,
+            next,
+            remaining,
+            opened | bit,
+            released + remaining * valves.rates[next],
+            best,
+        );
+    }
+}
+
+/// Best release of a single agent that opens at most the valves in each set.
+fn best_per_subset(valves: &CompressedNetwork, time: u32) -> Vec<u32> {
+    let mut best = vec![0; 1 << valves.valve_count()];
+
+    visit(valves, valves.start(), time, 0, 0, &mut best);
+
+    for bit in 0..valves.valve_count() {
+        for set in 0..best.len() {
+            if set & (1 << bit) != 0 {
+                best[set] = best[set].max(best[set ^ (1 << bit)]);
+            }
+        }
+    }
+
+    best
+}
+
+/// Best single agent route through the `allowed` valves as the release and the valve ids in order.
+fn best_route(
+    valves: &CompressedNetwork,
+    pos: usize,
+    time: u32,
+    allowed: u64,
+) -> (u32, Vec<usize>) {
+    let mut best = (0, vec![]);
+
+    for next in 0..valves.valve_count() {
+        let bit = 1 << next;
+        let Some(cost) = valves.distance(pos, next).checked_add(1) else {
+            continue;
+        };
+
+        if allowed & bit == 0 || cost >= time {
+            continue;
+        }
+
+        let remaining = time - cost;
+        let (released, mut route) = best_route(valves, next, remaining, allowed & !bit);
+        let released = released + remaining * valves.rates[next];
+
+        if released > best.0 {
+            route.insert(0, next);
+            best = (released, route);
+        }
+    }
+
+    best
+}
+
+/// Table of the best release per valve set for one up to `agents` agents.
+///
+/// Every agent opens a disjoint set of valves, so the table of a single agent is combined over all
+/// ways to split the valves.
+fn release_tables(valves: &CompressedNetwork, agents: usize, time: u32) -> Vec<Vec<u32>> {
+    assert!(
+        valves.valve_count() < 64,
+        "too many valves for a u64 bitmask"
+    );
+
+    let single = best_per_subset(valves, time);
+    let mut tables = vec![single.clone()];
+
+    for _ in 1..agents {
+        let previous = tables.last().unwrap();
+        let combined = (0..previous.len())
+            .map(|set| {
+                let mut best = previous[set];
+                let mut subset = set;
+
+                while subset != 0 {
+                    best = best.max(single[subset] + previous[set ^ subset]);
+                    subset = (subset - 1) & set;
+                }
+
+                best
+            })
+            .collect();
+
+        tables.push(combined);
+    }
+
+    tables
+}
+
+/// Plan of `agents` agents starting at `start` that releases the most pressure within `time` minutes.
+pub fn find_best_plan(network: &Network, start: &str, agents: usize, time: u32) -> Plan {
+    if agents == 0 {
+        return Plan {
+            released: 0,
+            time,
+            agents: vec![],
+        };
+    }
+
+    let valves = network.compress(start);
+    let tables = release_tables(&valves, agents, time);
+    let single = &tables[0];
+
+    // Walk back through the tables to find the valves of every agent
+    let mut set = single.len() - 1;
+    let mut assigned = vec![];
+
+    for agent in (1..agents).rev() {
+        let target = tables[agent][set];
+        let mut subset = set;
+
+        while single[subset] + tables[agent - 1][set ^ subset] != target {
+            subset = (subset - 1) & set;
+        }
+
+        assigned.push(subset);
+        set ^= subset;
+    }
+
+    assigned.push(set);
+    assigned.reverse();
+
+    let mut released = 0;
+    let agents = assigned
+        .into_iter()
+        .map(|allowed| {
+            let (agent_released, route) = best_route(&valves, valves.start(), time, allowed as u64);
+            released += agent_released;
+
+            let mut steps = vec![];
+            let mut minute = 0;
+            let mut position = start;
+
+            for valve in route {
+                let name = valves.names[valve].as_str();
+
+                for node in network.path(position, name).into_iter().skip(1) {
+                    minute += 1;
+                    steps.push(Step {
+                        minute,
+                        valve: node,
+                        action: Action::Move,
+                    });
+                }
+
+                minute += 1;
+                steps.push(Step {
+                    minute,
+                    valve: name.to_string(),
+                    action: Action::Open,
+                });
+                position = name;
+            }
+
+            steps
+        })
+        .collect();
+
+    Plan {
+        released,
+        time,
+        agents,
+    }
+}
+
+pub fn find_solo_order(network: &Network, start: &str) -> Plan {
+    find_best_plan(network, start, 1, 30)
+}
+
+pub fn find_elephant_order(network: &Network, start: &str) -> Plan {
+    find_best_plan(network, start, 2, 26)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_best_plan, find_elephant_order, find_solo_order};
+    use crate::{network::Network, plan::Action};
+
+    const TEST: &str = include_str!("test");
+
+    #[test]
+    fn test_paths() {
+        let network = Network::from(TEST);
+
+        let plan = find_solo_order(&network, "AA");
+
+        assert_eq!(plan.released, 1651);
+    }
+
+    #[test]
+    fn test_pathing() {
+        let network = Network::from(TEST);
+
+        let plan = find_elephant_order(&network, "AA");
+
+        assert_eq!(plan.released, 1707);
+    }
+
+    #[test]
+    fn more_agents_never_release_less() {
+        let network = Network::from(TEST);
+
+        let releases: Vec<_> = (0..4)
+            .map(|agents| find_best_plan(&network, "AA", agents, 26).released)
+            .collect();
+
+        assert_eq!(releases[0], 0);
+        assert!(releases.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+
+    #[test]
+    fn other_start() {
+        let network = Network::from(TEST);
+
+        // Starting at JJ the first valve can be opened right away
+        assert_eq!(find_best_plan(&network, "JJ", 1, 2).released, 21);
+    }
+
+    #[test]
+    fn unreachable_valves_stay_closed() {
+        let network = Network::from(
+            "Valve AA has flow rate=0; tunnel leads to valve BB
+Valve BB has flow rate=10; tunnel leads to valve AA
+Valve CC has flow rate=100; tunnel leads to valve DD
+Valve DD has flow rate=0; tunnel leads to valve CC",
+        );
+
+        assert_eq!(find_solo_order(&network, "AA").released, 28 * 10);
+        assert_eq!(find_elephant_order(&network, "AA").released, 24 * 10);
+    }
+
+    #[test]
+    fn solo_plan_matches_example() {
+        let network = Network::from(TEST);
+
+        let plan = find_solo_order(&network, "AA");
+
+        let opened: Vec<_> = plan.agents[0]
+            .iter()
+            .filter(|step| step.action == Action::Open)
+            .map(|step| (step.minute, step.valve.as_str()))
+            .collect();
+
+        assert_eq!(
+            opened,
+            [
+                (2, "DD"),
+                (5, "BB"),
+                (9, "JJ"),
+                (17, "HH"),
+                (21, "EE"),
+                (24, "CC")
+            ]
+        );
+    }
+
+    #[test]
+    fn solo_replay_matches_example() {
+        const EXPECTED_START: &str = "== Minute 1 ==
+No valves are open.
+You move to valve DD.
+
+== Minute 2 ==
+No valves are open.
+You open valve DD.
+
+== Minute 3 ==
+Valve DD is open, releasing 20 pressure.
+You move to valve CC.
+
+== Minute 4 ==
+Valve DD is open, releasing 20 pressure.
+You move to valve BB.
+
+== Minute 5 ==
+Valve DD is open, releasing 20 pressure.
+You open valve BB.
+
+== Minute 6 ==
+Valves BB and DD are open, releasing 33 pressure.
+You move to valve AA.
+";
+        const EXPECTED_END: &str = "== Minute 30 ==
+Valves BB, CC, DD, EE, HH, and JJ are open, releasing 81 pressure.
+
+";
+        let network = Network::from(TEST);
+
+        let replay = find_solo_order(&network, "AA").replay(&network);
+
+        assert!(replay.starts_with(EXPECTED_START));
+        assert!(replay.ends_with(EXPECTED_END));
+    }
+
+    #[test]
+    fn elephant_replay_releases_total() {
+        let network = Network::from(TEST);
+
+        let plan = find_elephant_order(&network, "AA");
+        let replay = plan.replay(&network);
+
+        let released: u32 = replay
+            .lines()
+            .filter_map(|line| line.split_once("releasing "))
+            .map(|(_, pressure)| pressure.split_once(' ').unwrap().0.parse::<u32>().unwrap())
+            .sum();
+
+        assert_eq!(released, plan.released);
+        assert!(replay.contains("The elephant opens valve"));
+    }
+}
@@ -4,42 +4,38 @@ pub enum Direction {
     Right,
 }
 
-pub fn get_direction_iter(input: &str) -> impl Iterator<Item = Direction> + '_ {
+pub fn parse_directions(input: &str) -> Vec<Direction> {
     input
+        .trim()
         .chars()
         .map(|c| match c {
             '<' => Direction::Left,
             '>' => Direction::Right,
             _ => panic!("invalid char {} detected", c),
         })
-        .cycle()
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use crate::direction_stream::Direction;
 
-    use super::get_direction_iter;
+    use super::parse_directions;
 
     #[test]
     fn simple_test() {
-        let stream: Vec<_> = get_direction_iter("<>").take(2).collect();
+        let stream = parse_directions("<>");
 
         assert_eq!(stream, vec![Direction::Left, Direction::Right]);
     }
 
     #[test]
-    fn repat_test() {
-        let stream: Vec<_> = get_direction_iter("<>").take(4).collect();
+    fn trailing_newline_test() {
+        let stream = parse_directions("<<>\n");
 
         assert_eq!(
             stream,
-            vec![
-                Direction::Left,
-                Direction::Right,
-                Direction::Left,
-                Direction::Right
-            ]
+            vec![Direction::Left, Direction::Left, Direction::Right]
         );
     }
 }
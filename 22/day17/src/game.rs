@@ -1,136 +1,322 @@
 use std::fmt::Display;
 
 use crate::{
-    direction_stream::{get_direction_iter, Direction},
-    shapes::{get_shapes_iter, Shape},
+    direction_stream::{parse_directions, Direction},
+    shapes::{get_shapes, RowMask, Shape},
 };
 
-struct Row {
-    cells: [bool; 7],
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameConfig {
+    pub width: usize,
+    /// Distance of a new rock from the left wall and from the top of the tower
+    pub spawn_offset: (usize, usize),
+    pub shapes: Vec<Shape>,
 }
 
-impl Row {
-    fn new() -> Self {
-        Row { cells: [false; 7] }
+impl Default for GameConfig {
+    fn default() -> Self {
+        GameConfig {
+            width: 7,
+            spawn_offset: (2, 3),
+            shapes: get_shapes(),
+        }
     }
 }
 
+/// Everything that decides how the tower grows from here on.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StateKey {
+    pub shape: usize,
+    pub jet: usize,
+    /// Rows above the sealed floor from the top down
+    pub surface: Vec<RowMask>,
+}
+
 pub struct Game {
     pub height: usize,
     pub drops: usize,
-    rows: Vec<Row>,
-    direction_stream: Box<dyn Iterator<Item = Direction>>,
-    shapes: Box<dyn Iterator<Item = Shape>>,
+    /// Rows from `base` upwards, everything below can not be reached anymore
+    rows: Vec<RowMask>,
+    base: usize,
+    width: usize,
+    full_row: RowMask,
+    spawn_offset: (usize, usize),
+    directions: Vec<Direction>,
+    next_direction: usize,
+    shapes: Vec<Shape>,
+    next_shape: usize,
 }
 
 impl Game {
-    pub fn new(input: &'static str) -> Self {
+    pub fn new(input: &str) -> Self {
+        Game::with_config(input, GameConfig::default())
+    }
+
+    pub fn with_config(input: &str, config: GameConfig) -> Self {
+        assert!(
+            (1..=RowMask::BITS as usize).contains(&config.width),
+            "unsupported chamber width {}",
+            config.width
+        );
+        assert!(!config.shapes.is_empty(), "need at least one shape");
+        assert!(
+            config
+                .shapes
+                .iter()
+                .all(|shape| config.spawn_offset.0 + shape.width <= config.width),
+            "shape does not fit into the chamber"
+        );
+
         Self {
             height: 0,
             drops: 0,
             rows: vec![],
-            direction_stream: Box::new(get_direction_iter(input)),
-            shapes: Box::new(get_shapes_iter()),
+            base: 0,
+            width: config.width,
+            full_row: RowMask::MAX >> (RowMask::BITS as usize - config.width),
+            spawn_offset: config.spawn_offset,
+            directions: parse_directions(input),
+            next_direction: 0,
+            shapes: config.shapes,
+            next_shape: 0,
         }
     }
 
-    fn check_collision(&self, shape: &Shape, pos: &(usize, usize)) -> bool {
-        shape.rows.iter().enumerate().any(|(idx, shape_row)| {
-            let tower_row = &self.rows[pos.1 + idx].cells[pos.0..(pos.0 + shape.width)];
-            assert_eq!(shape_row.len(), tower_row.len());
-
-            shape_row.iter().zip(tower_row).any(|(&s, &t)| s && t)
-        })
+    fn row(&self, y: usize) -> RowMask {
+        if y < self.base {
+            self.full_row
+        } else {
+            self.rows.get(y - self.base).copied().unwrap_or(0)
+        }
     }
-    pub fn drop_rock(&mut self) -> (usize, usize) {
-        let shape = self.shapes.next().unwrap();
 
-        let new_rows = shape.height + 3;
+    fn check_collision(&self, shape: &Shape, pos: &(usize, usize)) -> bool {
+        shape
+            .rows
+            .iter()
+            .enumerate()
+            .any(|(idx, shape_row)| (shape_row << pos.0) & self.row(pos.1 + idx) != 0)
+    }
 
-        self.rows.resize_with(self.height + new_rows, || Row::new());
+    pub fn drop_rock(&mut self) -> (usize, usize) {
+        let shape = self.shapes[self.next_shape].clone();
+        self.next_shape = (self.next_shape + 1) % self.shapes.len();
 
-        let mut pos = (2usize, self.height + 3);
+        let mut pos = (self.spawn_offset.0, self.height + self.spawn_offset.1);
 
         loop {
-            let dir = self.direction_stream.next().unwrap();
-            let new_x = match dir {
-                Direction::Left => {
-                    if pos.0 == 0 {
-                        0
-                    } else {
-                        let test_pos = (pos.0 - 1, pos.1);
-                        if self.check_collision(&shape, &test_pos) {
-                            pos.0
-                        } else {
-                            pos.0 - 1
-                        }
-                    }
-                }
-                Direction::Right => {
-                    if pos.0 + shape.width < 7 {
-                        let test_pos = (pos.0 + 1, pos.1);
-                        if self.check_collision(&shape, &test_pos) {
-                            pos.0
-                        } else {
-                            pos.0 + 1
-                        }
-                    } else {
-                        pos.0
-                    }
-                }
-            };
+            let dir = self.directions[self.next_direction];
+            self.next_direction = (self.next_direction + 1) % self.directions.len();
 
-            pos.0 = new_x;
+            let test_x = match dir {
+                Direction::Left => pos.0.checked_sub(1),
+                Direction::Right => Some(pos.0 + 1).filter(|x| x + shape.width <= self.width),
+            };
 
-            if pos.1 == 0 {
-                break;
+            if let Some(test_x) = test_x {
+                if !self.check_collision(&shape, &(test_x, pos.1)) {
+                    pos.0 = test_x;
+                }
             }
 
-            let new_y = pos.1 - 1;
-
-            let test_pos = (pos.0, new_y);
-            let collision = self.check_collision(&shape, &test_pos);
-
-            if collision {
+            if pos.1 == 0 || self.check_collision(&shape, &(pos.0, pos.1 - 1)) {
                 break;
             }
-            pos.1 = new_y;
+
+            pos.1 -= 1;
         }
 
+        let top = pos.1 + shape.height;
+        self.rows.resize(top.max(self.height) - self.base, 0);
+
         for (idx, shape_row) in shape.rows.iter().enumerate() {
-            let tower_row = &mut self.rows[pos.1 + idx].cells[pos.0..(pos.0 + shape.width)];
-
-            tower_row
-                .iter_mut()
-                .zip(shape_row.iter())
-                .for_each(|(t, s)| {
-                    *t |= s;
-                });
+            self.rows[pos.1 + idx - self.base] |= shape_row << pos.0;
         }
 
         self.drops += 1;
-        self.height = self.rows.len()
-            - self
-                .rows
-                .iter()
-                .rev()
-                .take_while(|r| r.cells.iter().all(|c| !c))
-                .count();
+        self.height = self.height.max(top);
+        self.trim();
 
         pos
     }
+
+    /// Drops all rows that no rock can reach anymore.
+    ///
+    /// Rocks only move down and sideways, so going down row by row finds all reachable cells. The row
+    /// below the lowest reachable cell is kept for collision checks.
+    fn trim(&mut self) {
+        let mut reachable = self.full_row;
+        let mut lowest = self.height;
+
+        for y in (self.base..self.height).rev() {
+            let free = !self.row(y) & self.full_row;
+            reachable &= free;
+
+            loop {
+                let spread = (reachable | (reachable << 1) | (reachable >> 1)) & free;
+                if spread == reachable {
+                    break;
+                }
+                reachable = spread;
+            }
+
+            if reachable == 0 {
+                break;
+            }
+
+            lowest = y;
+        }
+
+        let keep_from = lowest.saturating_sub(1).max(self.base);
+
+        self.rows.drain(..keep_from - self.base);
+        self.base = keep_from;
+    }
+
+    pub fn state_key(&self) -> StateKey {
+        StateKey {
+            shape: self.next_shape,
+            jet: self.next_direction,
+            surface: self.rows.iter().rev().copied().collect(),
+        }
+    }
 }
 
 impl Display for Game {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         for row in self.rows.iter().rev() {
-            let row = row
-                .cells
-                .iter()
-                .map(|&c| if c { '#' } else { '.' })
+            let row = (0..self.width)
+                .map(|x| if row & (1 << x) != 0 { '#' } else { '.' })
                 .collect::<String>();
             writeln!(f, "{}", row)?
         }
-        writeln!(f, "-------")
+        writeln!(f, "{}", "-".repeat(self.width))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Game, GameConfig};
+    use crate::shapes::parse_shapes;
+
+    const TEST: &str = include_str!("test");
+
+    #[test]
+    fn first_rocks() {
+        const EXPECTED: &str = "...#...
+..###..
+...#...
+..####.
+-------
+";
+        let mut game = Game::new(TEST);
+
+        assert_eq!(game.drop_rock(), (2, 0));
+        assert_eq!(game.drop_rock(), (2, 1));
+
+        assert_eq!(game.height, 4);
+        assert_eq!(game.to_string(), EXPECTED);
+    }
+
+    #[test]
+    fn test_height() {
+        let mut game = Game::new(TEST);
+
+        for _ in 0..2022 {
+            game.drop_rock();
+        }
+
+        assert_eq!(game.height, 3068);
+    }
+
+    #[test]
+    fn window_stays_small() {
+        let mut game = Game::new(TEST);
+
+        for _ in 0..2022 {
+            game.drop_rock();
+            assert!(game.state_key().surface.len() < 100);
+        }
+    }
+
+    #[test]
+    fn single_cell_piece() {
+        const EXPECTED: &str = ".#.
+##.
+##.
+---
+";
+        let config = GameConfig {
+            width: 3,
+            spawn_offset: (0, 0),
+            shapes: parse_shapes("#"),
+        };
+        let mut game = Game::with_config("<><>>", config);
+
+        for _ in 0..5 {
+            game.drop_rock();
+        }
+
+        assert_eq!(game.height, 3);
+        assert_eq!(game.to_string(), EXPECTED);
+    }
+
+    #[test]
+    fn narrow_chamber_stacks() {
+        let config = GameConfig {
+            width: 1,
+            spawn_offset: (0, 3),
+            shapes: parse_shapes("#"),
+        };
+        let mut game = Game::with_config("<>", config);
+
+        for _ in 0..100 {
+            game.drop_rock();
+        }
+
+        assert_eq!(game.height, 100);
+        assert!(game.state_key().surface.len() <= 1);
+    }
+
+    #[test]
+    fn padded_piece_behaves_like_its_cells() {
+        let padded = GameConfig {
+            width: 3,
+            spawn_offset: (0, 0),
+            shapes: parse_shapes(".#."),
+        };
+        let mut game = Game::with_config("<><>>", padded);
+
+        for _ in 0..5 {
+            game.drop_rock();
+        }
+
+        assert_eq!(game.to_string(), ".#.\n##.\n##.\n---\n");
+
+        // The empty columns around the cell must not keep it out of a narrow chamber
+        let narrow = GameConfig {
+            width: 1,
+            spawn_offset: (0, 3),
+            shapes: parse_shapes("..#..\n"),
+        };
+        let mut game = Game::with_config("<>", narrow);
+        game.drop_rock();
+
+        assert_eq!(game.height, 1);
+    }
+
+    #[test]
+    fn wide_chamber() {
+        let config = GameConfig {
+            width: 20,
+            ..Default::default()
+        };
+        let mut game = Game::with_config(TEST, config);
+
+        for _ in 0..2022 {
+            game.drop_rock();
+        }
+
+        assert!(game.height < 3068);
     }
 }
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 mod direction_stream;
 mod game;
 mod shapes;
@@ -21,23 +23,50 @@ fn part1() {
     println!("Part 1 {}", game.height);
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-struct State {
-    x: usize,
+fn part2() {
+    const INPUT: &str = include_str!("input");
+
+    println!("Part 2 {}", tower_height(INPUT, 1000000000000));
 }
 
-fn part2() {
-    // const INPUT: &str = include_str!("input");
-    // let mut game = Game::new(INPUT);
+/// Height after `rocks` drops, skipping ahead once the tower starts repeating itself.
+fn tower_height(input: &str, rocks: usize) -> usize {
+    let mut game = Game::new(input);
+    let mut seen = HashMap::new();
+    let mut skipped_height = 0;
+
+    while game.drops < rocks {
+        game.drop_rock();
 
-    // let mut states = vec![];
-    // let pos = game.drop_rock();
+        if skipped_height > 0 {
+            continue;
+        }
 
-    // game.drop_rock();
+        if let Some((drops, height)) = seen.insert(game.state_key(), (game.drops, game.height)) {
+            let period = game.drops - drops;
+            let cycles = (rocks - game.drops) / period;
 
-    // loop {
-    //     game.drop_rock();
+            skipped_height = cycles * (game.height - height);
+            game.drops += cycles * period;
+        }
+    }
 
-    //     for prefix_len in 0..=game.drops - 2 {}
-    // }
+    game.height + skipped_height
+}
+
+#[cfg(test)]
+mod tests {
+    use super::tower_height;
+
+    const TEST: &str = include_str!("test");
+
+    #[test]
+    fn short_tower() {
+        assert_eq!(tower_height(TEST, 2022), 3068);
+    }
+
+    #[test]
+    fn huge_tower() {
+        assert_eq!(tower_height(TEST, 1000000000000), 1514285714288);
+    }
 }
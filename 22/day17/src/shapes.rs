@@ -1,60 +1,134 @@
+/// Cells of one row of a shape or the chamber, bit `x` stands for column `x`
+pub type RowMask = u32;
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Shape {
     pub width: usize,
     pub height: usize,
-    pub rows: Vec<Vec<bool>>,
+    /// Bit `x` of a row is set if the cell in column `x` is solid, the bottom row comes first
+    pub rows: Vec<RowMask>,
+}
+
+/// Parses pieces drawn with `#` and `.` as in the puzzle text, separated by empty lines.
+pub fn parse_shapes(input: &str) -> Vec<Shape> {
+    input
+        .replace("\r\n", "\n")
+        .split("\n\n")
+        .filter(|block| !block.trim().is_empty())
+        .map(|block| {
+            let lines: Vec<_> = block.lines().filter(|line| !line.is_empty()).collect();
+
+            let rows: Vec<RowMask> = lines
+                .iter()
+                .rev()
+                .map(|line| {
+                    assert!(
+                        line.chars().count() <= RowMask::BITS as usize,
+                        "shape line {line:?} is wider than {} columns",
+                        RowMask::BITS
+                    );
+
+                    line.chars().enumerate().fold(0, |row, (x, c)| match c {
+                        '#' => row | (1 << x),
+                        '.' => row,
+                        _ => panic!("invalid char {} in shape", c),
+                    })
+                })
+                .collect();
+
+            assert!(rows.iter().all(|row| *row != 0), "empty row in shape");
+
+            // Columns that are empty in every row are not part of the piece
+            let columns = rows.iter().fold(0, |acc, row| acc | row);
+            let offset = columns.trailing_zeros();
+            let rows: Vec<RowMask> = rows.into_iter().map(|row| row >> offset).collect();
+            let width = (RowMask::BITS - (columns >> offset).leading_zeros()) as usize;
+
+            Shape {
+                width,
+                height: rows.len(),
+                rows,
+            }
+        })
+        .collect()
 }
 
-pub fn get_shapes_iter() -> impl Iterator<Item = Shape> {
-    let shapes = vec![
-        Shape {
-            width: 4,
-            height: 1,
-            rows: vec![vec![true, true, true, true]],
-        },
-        Shape {
-            width: 3,
-            height: 3,
-            rows: vec![
-                vec![false, true, false],
-                vec![true, true, true],
-                vec![false, true, false],
-            ],
-        },
-        Shape {
-            width: 3,
-            height: 3,
-            rows: vec![
-                vec![true, true, true],
-                vec![false, false, true],
-                vec![false, false, true],
-            ],
-        },
-        Shape {
-            width: 1,
-            height: 4,
-            rows: vec![vec![true], vec![true], vec![true], vec![true]],
-        },
-        Shape {
-            width: 2,
-            height: 2,
-            rows: vec![vec![true, true], vec![true, true]],
-        },
-    ];
-
-    shapes.into_iter().cycle()
+pub fn get_shapes() -> Vec<Shape> {
+    parse_shapes(include_str!("shapes"))
 }
 
 #[cfg(test)]
 mod tests {
-    use super::get_shapes_iter;
+    use super::{get_shapes, parse_shapes, Shape};
+
+    #[test]
+    fn shapes_fit_their_size() {
+        let shapes = get_shapes();
+
+        assert_eq!(shapes.len(), 5);
+
+        for shape in shapes {
+            assert_eq!(shape.rows.len(), shape.height);
+            assert_eq!(
+                shape.rows.iter().fold(0, |acc, row| acc | row),
+                (1 << shape.width) - 1
+            );
+        }
+    }
+
+    #[test]
+    fn parse_bottom_first() {
+        let shapes = parse_shapes("..#\n..#\n###\n\n#\n");
+
+        assert_eq!(
+            shapes,
+            vec![
+                Shape {
+                    width: 3,
+                    height: 3,
+                    rows: vec![0b111, 0b100, 0b100],
+                },
+                Shape {
+                    width: 1,
+                    height: 1,
+                    rows: vec![0b1],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "wider than 32 columns")]
+    fn too_wide_shape() {
+        parse_shapes(&"#".repeat(33));
+    }
+
+    #[test]
+    fn widest_shape() {
+        let shapes = parse_shapes(&"#".repeat(32));
+
+        assert_eq!(shapes[0].width, 32);
+        assert_eq!(shapes[0].rows, vec![u32::MAX]);
+    }
 
     #[test]
-    fn check_repeats() {
-        let shapes: Vec<_> = get_shapes_iter().take(10).collect();
+    fn empty_columns_are_trimmed() {
+        let shapes = parse_shapes("..#.\n\n.#\n##\n");
 
-        (0..5)
-            .into_iter()
-            .for_each(|idx| assert_eq!(shapes[idx], shapes[idx + 5]));
+        assert_eq!(
+            shapes,
+            vec![
+                Shape {
+                    width: 1,
+                    height: 1,
+                    rows: vec![0b1],
+                },
+                Shape {
+                    width: 2,
+                    height: 2,
+                    rows: vec![0b11, 0b10],
+                },
+            ]
+        );
     }
 }
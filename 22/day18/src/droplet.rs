@@ -1,4 +1,6 @@
-use std::ops::Add;
+use std::{collections::HashSet, ops::Add};
+
+use crate::sparse::find_enclosed_cells;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Cell {
@@ -7,10 +9,36 @@ pub enum Cell {
     Outside,
 }
 
-#[derive(Debug, Clone, Copy)]
-pub struct Coord(isize, isize, isize);
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Coord(pub isize, pub isize, pub isize);
+
+impl Add for Coord {
+    type Output = Coord;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Coord(self.0 + rhs.0, self.1 + rhs.1, self.2 + rhs.2)
+    }
+}
+
+impl Coord {
+    pub fn min(self, other: Self) -> Self {
+        Coord(
+            self.0.min(other.0),
+            self.1.min(other.1),
+            self.2.min(other.2),
+        )
+    }
+
+    pub fn max(self, other: Self) -> Self {
+        Coord(
+            self.0.max(other.0),
+            self.1.max(other.1),
+            self.2.max(other.2),
+        )
+    }
+}
 
-const ORTHO_NEIGHBOURS: [Coord; 6] = [
+pub const ORTHO_NEIGHBOURS: [Coord; 6] = [
     Coord(-1, 0, 0),
     Coord(1, 0, 0),
     Coord(0, -1, 0),
@@ -19,7 +47,7 @@ const ORTHO_NEIGHBOURS: [Coord; 6] = [
     Coord(0, 0, 1),
 ];
 
-const NEIGHBOURS: [Coord; 26] = [
+pub const NEIGHBOURS: [Coord; 26] = [
     // lower
     Coord(-1, -1, -1),
     Coord(0, -1, -1),
@@ -51,128 +79,239 @@ const NEIGHBOURS: [Coord; 26] = [
     Coord(1, 1, 1),
 ];
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Surface {
+    /// Every face that does not touch lava
+    Total,
+    /// Only faces that can be reached from outside
+    Outer,
+}
+
+/// Connected region of air that is completely enclosed by lava.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AirPocket {
+    pub volume: usize,
+    pub min: Coord,
+    pub max: Coord,
+    /// Lava faces bordering the pocket, they count towards the total but not the outer surface
+    pub lava_faces: usize,
+}
+
+/// Dense grids above this many cells fall back to the sparse representation.
+const DENSE_LIMIT: isize = 1 << 24;
+
+enum Cells {
+    /// Every cell of the bounding box
+    Dense(Box<[Cell]>),
+    /// Only the lava and the enclosed air, everything else is outside
+    Sparse {
+        lava: HashSet<Coord>,
+        inside: HashSet<Coord>,
+    },
+}
+
+/// Lava cubes in a bounding box that has one cell of padding on every side.
 pub struct Droplet {
-    cells: Box<[Cell]>,
+    cells: Cells,
+    lava: Vec<Coord>,
+    min: Coord,
     width: isize,
     height: isize,
     depth: isize,
 }
 
-impl Droplet {
-    fn from_coordinates(coordinates: Vec<Coord>) -> Self {
-        let max = coordinates
+/// Bounding box of the lava with one cell of padding on every side.
+fn padded_bounds(lava: &[Coord]) -> (Coord, Coord) {
+    let (min, max) = match lava.first() {
+        Some(first) => lava
             .iter()
-            .fold(Coord(0, 0, 0), |Coord(mx, my, mz), Coord(ex, ey, ez)| {
-                Coord(mx.max(*ex), my.max(*ey), mz.max(*ez))
-            });
-
-        let width = max.0 + 1;
-        let height = max.1 + 1;
-        let depth = max.2 + 1;
-        let vec = vec![Cell::Inside; (width * height * depth) as usize];
-
-        let mut droplet = Self {
-            cells: vec.into_boxed_slice(),
-            width,
-            height,
-            depth,
-        };
+            .fold((*first, *first), |(min, max), c| (min.min(*c), max.max(*c))),
+        None => (Coord(0, 0, 0), Coord(0, 0, 0)),
+    };
 
-        for coordinate in coordinates {
-            let cell = droplet
-                .get_cell_mut(coordinate)
-                .expect("coord out of range during construction");
+    (min + Coord(-1, -1, -1), max + Coord(1, 1, 1))
+}
 
-            *cell = Cell::Lava;
-        }
+/// Index of `coord` in a grid of `size` cells starting at `min`, `None` outside of the grid.
+fn grid_index(min: Coord, size: Coord, coord: Coord) -> Option<usize> {
+    let Coord(x, y, z) = Coord(coord.0 - min.0, coord.1 - min.1, coord.2 - min.2);
 
-        let mut stack = vec![Coord(0, 0, 0)];
+    if x < 0 || y < 0 || z < 0 {
+        return None;
+    }
 
-        while let Some(c) = stack.pop() {
-            if let Some(cell) = droplet.get_cell_mut(c) {
-                *cell = Cell::Outside;
-                ORTHO_NEIGHBOURS
-                    .iter()
-                    .map(|n| Coord(c.0 + n.0, c.1 + n.1, c.2 + n.2))
-                    .for_each(|n| match droplet.get_cell(n) {
-                        Some(&Cell::Inside) => stack.push(n),
-                        _ => {}
-                    });
-            }
-        }
+    if x >= size.0 || y >= size.1 || z >= size.2 {
+        return None;
+    }
 
-        droplet
+    Some((y * size.0 * size.2 + z * size.0 + x) as usize)
+}
+
+impl Droplet {
+    fn from_coordinates(coordinates: Vec<Coord>) -> Self {
+        let (min, max) = padded_bounds(&coordinates);
+        let volume = (max.0 - min.0 + 1)
+            .checked_mul(max.1 - min.1 + 1)
+            .and_then(|area| area.checked_mul(max.2 - min.2 + 1));
+
+        match volume {
+            Some(volume) if volume <= DENSE_LIMIT => Droplet::dense(coordinates),
+            _ => Droplet::sparse(coordinates),
+        }
     }
 
-    pub fn coord_to_usize(&self, coord: Coord) -> Option<isize> {
-        if coord.0 < 0 || coord.1 < 0 || coord.2 < 0 {
-            return None;
+    fn with_cells(lava: Vec<Coord>, cells: Cells, (min, max): (Coord, Coord)) -> Self {
+        Self {
+            cells,
+            lava,
+            min,
+            width: max.0 - min.0 + 1,
+            height: max.1 - min.1 + 1,
+            depth: max.2 - min.2 + 1,
         }
+    }
+
+    pub fn dense(coordinates: Vec<Coord>) -> Self {
+        let (min, max) = padded_bounds(&coordinates);
+        let size = Coord(max.0 - min.0 + 1, max.1 - min.1 + 1, max.2 - min.2 + 1);
+        let mut cells = vec![Cell::Inside; (size.0 * size.1 * size.2) as usize].into_boxed_slice();
 
-        if coord.0 > self.width || coord.1 > self.height || coord.2 > self.depth {
-            return None;
+        for coordinate in &coordinates {
+            let index =
+                grid_index(min, size, *coordinate).expect("coord out of range during construction");
+
+            cells[index] = Cell::Lava;
         }
 
-        Some(coord.1 * self.width * self.depth + coord.2 * self.width + coord.0)
-    }
+        // The padding guarantees that the corner is not lava
+        let mut stack = vec![min];
+        cells[0] = Cell::Outside;
 
-    pub fn get_cell(&self, coord: Coord) -> Option<&Cell> {
-        let index = self.coord_to_usize(coord)?;
+        while let Some(c) = stack.pop() {
+            for n in ORTHO_NEIGHBOURS.iter().map(|n| c + *n) {
+                if let Some(index) = grid_index(min, size, n) {
+                    if cells[index] == Cell::Inside {
+                        cells[index] = Cell::Outside;
+                        stack.push(n);
+                    }
+                }
+            }
+        }
 
-        self.cells.get(index as usize)
+        Droplet::with_cells(coordinates, Cells::Dense(cells), (min, max))
     }
 
-    pub fn get_cell_mut(&mut self, coord: Coord) -> Option<&mut Cell> {
-        let index = self.coord_to_usize(coord)?;
+    pub fn sparse(coordinates: Vec<Coord>) -> Self {
+        let (min, max) = padded_bounds(&coordinates);
+        let lava: HashSet<_> = coordinates.iter().copied().collect();
+        let inside = find_enclosed_cells(&lava, min, max);
 
-        self.cells.get_mut(index as usize)
+        Droplet::with_cells(coordinates, Cells::Sparse { lava, inside }, (min, max))
     }
 
-    fn get_surface_area(
-        &self,
-        check_fn: fn(cell: &Cell, neighbour: Option<&Cell>) -> Option<()>,
-    ) -> usize {
-        let mut surface_area = 0;
+    pub fn coord_to_usize(&self, coord: Coord) -> Option<usize> {
+        grid_index(self.min, Coord(self.width, self.height, self.depth), coord)
+    }
 
-        for y in 0..self.height {
-            for z in 0..self.depth {
-                for x in 0..self.width {
-                    let coord = Coord(x, y, z);
-                    let cell = self.get_cell(coord).unwrap();
-                    if let Some(&Cell::Lava) = self.get_cell(coord) {
-                        surface_area += ORTHO_NEIGHBOURS
-                            .iter()
-                            .map(|Coord(nx, ny, nz)| Coord(nx + x, ny + y, nz + z))
-                            .filter_map(|c| check_fn(cell, self.get_cell(c)))
-                            .count();
-                    }
+    /// Cells outside of the bounding box are always outside.
+    pub fn get_cell(&self, coord: Coord) -> Cell {
+        match &self.cells {
+            Cells::Dense(cells) => self
+                .coord_to_usize(coord)
+                .map_or(Cell::Outside, |index| cells[index]),
+            Cells::Sparse { lava, inside } => {
+                if lava.contains(&coord) {
+                    Cell::Lava
+                } else if inside.contains(&coord) {
+                    Cell::Inside
+                } else {
+                    Cell::Outside
                 }
             }
         }
-        surface_area
     }
 
-    fn total_check(_cell: &Cell, neighbour: Option<&Cell>) -> Option<()> {
-        if let Some(Cell::Lava) = neighbour {
-            None
-        } else {
-            Some(())
-        }
+    /// Lava cells with the direction of each of their faces that counts towards the surface.
+    pub fn exposed_faces(&self, surface: Surface) -> Vec<(Coord, Coord)> {
+        let check_fn = match surface {
+            Surface::Total => Droplet::total_check,
+            Surface::Outer => Droplet::outer_check,
+        };
+
+        self.lava
+            .iter()
+            .flat_map(|c| ORTHO_NEIGHBOURS.iter().map(|n| (*c, *n)))
+            .filter(|(c, n)| check_fn(self.get_cell(*c + *n)))
+            .collect()
     }
 
-    fn outer_check(_cell: &Cell, neighbour: Option<&Cell>) -> Option<()> {
-        match neighbour {
-            Some(&Cell::Outside) | None => Some(()),
-            _ => None,
-        }
+    fn total_check(neighbour: Cell) -> bool {
+        neighbour != Cell::Lava
+    }
+
+    fn outer_check(neighbour: Cell) -> bool {
+        neighbour == Cell::Outside
     }
 
     pub fn get_total_surface_area(&self) -> usize {
-        self.get_surface_area(Droplet::total_check)
+        self.exposed_faces(Surface::Total).len()
     }
 
     pub fn get_outer_surface_area(&self) -> usize {
-        self.get_surface_area(Droplet::outer_check)
+        self.exposed_faces(Surface::Outer).len()
+    }
+}
+
+impl Droplet {
+    fn inside_cells(&self) -> Vec<Coord> {
+        match &self.cells {
+            Cells::Dense(_) => {
+                let min = self.min;
+                (0..self.height)
+                    .flat_map(|y| (0..self.depth).map(move |z| (y, z)))
+                    .flat_map(|(y, z)| (0..self.width).map(move |x| min + Coord(x, y, z)))
+                    .filter(|c| self.get_cell(*c) == Cell::Inside)
+                    .collect()
+            }
+            Cells::Sparse { inside, .. } => inside.iter().copied().collect(),
+        }
+    }
+
+    /// Splits the enclosed air into connected pockets, ordered by their lowest corner.
+    pub fn air_pockets(&self) -> Vec<AirPocket> {
+        let mut unvisited: HashSet<_> = self.inside_cells().into_iter().collect();
+        let mut pockets = vec![];
+
+        while let Some(&start) = unvisited.iter().next() {
+            unvisited.remove(&start);
+
+            let mut pocket = AirPocket {
+                volume: 0,
+                min: start,
+                max: start,
+                lava_faces: 0,
+            };
+            let mut stack = vec![start];
+
+            while let Some(c) = stack.pop() {
+                pocket.volume += 1;
+                pocket.min = pocket.min.min(c);
+                pocket.max = pocket.max.max(c);
+
+                for n in ORTHO_NEIGHBOURS.iter().map(|n| c + *n) {
+                    if self.get_cell(n) == Cell::Lava {
+                        pocket.lava_faces += 1;
+                    } else if unvisited.remove(&n) {
+                        stack.push(n);
+                    }
+                }
+            }
+
+            pockets.push(pocket);
+        }
+
+        pockets.sort_unstable_by_key(|pocket| (pocket.min.0, pocket.min.1, pocket.min.2));
+        pockets
     }
 }
 
@@ -209,26 +348,29 @@ mod tests {
     fn simple_works() {
         let droplet: Droplet = "1,1,1\n2,1,1".into();
 
-        assert_eq!(droplet.width, 3);
-        assert_eq!(droplet.height, 2);
-        assert_eq!(droplet.depth, 2);
+        assert_eq!(droplet.width, 4);
+        assert_eq!(droplet.height, 3);
+        assert_eq!(droplet.depth, 3);
 
-        assert_eq!(droplet.coord_to_usize(Coord(1, 1, 1)), Some(10));
-        assert_eq!(droplet.coord_to_usize(Coord(2, 1, 1)), Some(11));
+        assert_eq!(droplet.coord_to_usize(Coord(0, 0, 0)), Some(0));
+        assert_eq!(droplet.coord_to_usize(Coord(1, 1, 1)), Some(17));
+        assert_eq!(droplet.coord_to_usize(Coord(2, 1, 1)), Some(18));
+        assert_eq!(droplet.coord_to_usize(Coord(4, 1, 1)), None);
+        assert_eq!(droplet.coord_to_usize(Coord(-1, 1, 1)), None);
     }
 
     #[test]
     fn lava_cell_set_correctly() {
         let droplet: Droplet = "1,1,1\n2,1,1".into();
 
-        assert_eq!(droplet.get_cell(Coord(1, 1, 1)), Some(&Cell::Lava))
+        assert_eq!(droplet.get_cell(Coord(1, 1, 1)), Cell::Lava)
     }
 
     #[test]
-    fn Inside_cell_set_correctly() {
+    fn padding_cell_is_outside() {
         let droplet: Droplet = "1,1,1\n2,1,1".into();
 
-        assert_eq!(droplet.get_cell(Coord(0, 0, 0)), Some(&Cell::Inside))
+        assert_eq!(droplet.get_cell(Coord(0, 0, 0)), Cell::Outside)
     }
 
     #[test]
@@ -277,17 +419,140 @@ mod tests {
     fn get_outer_surface_area() {
         let droplet: Droplet = TEST.into();
 
-        let inside_cells: Vec<_> = droplet
-            .cells
-            .iter()
-            .enumerate()
-            .filter(|(_idx, c)| matches!(c, &Cell::Inside))
-            .collect();
-
-        assert_eq!(inside_cells.len(), 1);
+        assert_eq!(droplet.get_cell(Coord(2, 2, 5)), Cell::Inside);
 
         let outer_surface_area = droplet.get_outer_surface_area();
 
         assert_eq!(outer_surface_area, 58);
     }
+
+    #[test]
+    fn negative_coordinates() {
+        let droplet: Droplet = "-1,-1,-1\n-2,-1,-1".into();
+
+        assert_eq!(droplet.get_total_surface_area(), 10);
+        assert_eq!(droplet.get_outer_surface_area(), 10);
+    }
+
+    #[test]
+    fn sparse_matches_dense() {
+        for input in [TEST, include_str!("input")] {
+            let coordinates: Vec<_> = input
+                .lines()
+                .map(|l| {
+                    let mut split = l.split(',').map(|v| v.parse().unwrap());
+                    Coord(
+                        split.next().unwrap(),
+                        split.next().unwrap(),
+                        split.next().unwrap(),
+                    )
+                })
+                .collect();
+
+            let dense = Droplet::dense(coordinates.clone());
+            let sparse = Droplet::sparse(coordinates);
+
+            assert_eq!(
+                dense.get_total_surface_area(),
+                sparse.get_total_surface_area()
+            );
+            assert_eq!(
+                dense.get_outer_surface_area(),
+                sparse.get_outer_surface_area()
+            );
+        }
+    }
+
+    #[test]
+    fn far_apart_cubes_are_sparse() {
+        let droplet: Droplet = "0,0,0\n1000000,1000000,1000000".into();
+
+        assert!(matches!(droplet.cells, Cells::Sparse { .. }));
+        assert_eq!(droplet.get_total_surface_area(), 12);
+        assert_eq!(droplet.get_outer_surface_area(), 12);
+    }
+
+    #[test]
+    fn huge_bounding_box_does_not_overflow() {
+        let droplet: Droplet = "0,0,0\n4000000000000,-4000000000000,4000000000000".into();
+
+        assert!(matches!(droplet.cells, Cells::Sparse { .. }));
+        assert_eq!(droplet.get_outer_surface_area(), 12);
+    }
+
+    #[test]
+    fn sparse_hollow_cube() {
+        // 3x3x3 cube without its centre, far away from a second cube
+        let mut coordinates: Vec<_> = (0..27)
+            .map(|i| Coord(i % 3, i / 3 % 3, i / 9))
+            .filter(|c| *c != Coord(1, 1, 1))
+            .collect();
+        coordinates.push(Coord(5000000, 0, 0));
+
+        let droplet = Droplet::sparse(coordinates);
+
+        assert_eq!(droplet.get_cell(Coord(1, 1, 1)), Cell::Inside);
+        assert_eq!(droplet.get_total_surface_area(), 54 + 6 + 6);
+        assert_eq!(droplet.get_outer_surface_area(), 54 + 6);
+    }
+
+    #[test]
+    fn test_air_pocket() {
+        let droplet: Droplet = TEST.into();
+
+        assert_eq!(
+            droplet.air_pockets(),
+            vec![AirPocket {
+                volume: 1,
+                min: Coord(2, 2, 5),
+                max: Coord(2, 2, 5),
+                lava_faces: 6,
+            }]
+        );
+    }
+
+    #[test]
+    fn pockets_explain_surface_difference() {
+        let droplet: Droplet = include_str!("input").into();
+
+        let pocket_faces: usize = droplet
+            .air_pockets()
+            .iter()
+            .map(|pocket| pocket.lava_faces)
+            .sum();
+
+        assert_eq!(
+            droplet.get_total_surface_area() - droplet.get_outer_surface_area(),
+            pocket_faces
+        );
+    }
+
+    #[test]
+    fn separate_pockets() {
+        // Two hollow cubes next to each other, the left one with a 2x1x1 hole
+        let mut coordinates: Vec<_> = (0..36)
+            .map(|i| Coord(i % 4, i / 4 % 3, i / 12))
+            .filter(|c| !(c.1 == 1 && c.2 == 1 && (1..=2).contains(&c.0)))
+            .collect();
+        coordinates.extend(
+            (0..27)
+                .map(|i| Coord(10 + i % 3, i / 3 % 3, i / 9))
+                .filter(|c| *c != Coord(11, 1, 1)),
+        );
+
+        for droplet in [
+            Droplet::dense(coordinates.clone()),
+            Droplet::sparse(coordinates),
+        ] {
+            let pockets = droplet.air_pockets();
+
+            assert_eq!(pockets.len(), 2);
+            assert_eq!(pockets[0].volume, 2);
+            assert_eq!(pockets[0].min, Coord(1, 1, 1));
+            assert_eq!(pockets[0].max, Coord(2, 1, 1));
+            assert_eq!(pockets[0].lava_faces, 10);
+            assert_eq!(pockets[1].volume, 1);
+            assert_eq!(pockets[1].lava_faces, 6);
+        }
+    }
 }
@@ -1,6 +1,11 @@
+use std::{env, fs, path::Path};
+
 mod droplet;
+mod mesh;
+mod sparse;
 
-use droplet::Droplet;
+use droplet::{Droplet, Surface};
+use mesh::Mesh;
 
 const INPUT: &str = include_str!("input");
 
@@ -9,4 +14,24 @@ fn main() {
 
     println!("Part 1 {}", droplet.get_total_surface_area());
     println!("Part 2 {}", droplet.get_outer_surface_area());
+
+    for pocket in droplet.air_pockets() {
+        println!(
+            "Air pocket {:?}..{:?}: {} cells, {} lava faces",
+            pocket.min, pocket.max, pocket.volume, pocket.lava_faces
+        );
+    }
+
+    // Comparing both meshes in a viewer shows the air pockets
+    if let Some(directory) = env::args().nth(1) {
+        let directory = Path::new(&directory);
+
+        let total = Mesh::new(&droplet, Surface::Total, true);
+        fs::write(directory.join("droplet_total.obj"), total.to_obj())
+            .expect("could not write mesh");
+
+        let outer = Mesh::new(&droplet, Surface::Outer, true);
+        fs::write(directory.join("droplet_outer.stl"), outer.to_stl("droplet"))
+            .expect("could not write mesh");
+    }
 }
@@ -0,0 +1,278 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write,
+};
+
+use crate::droplet::{Coord, Droplet, Surface};
+
+/// Rectangle on the surface with its corners counter clockwise when looking against the normal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Quad {
+    pub normal: Coord,
+    pub corners: [Coord; 4],
+}
+
+pub struct Mesh {
+    pub quads: Vec<Quad>,
+}
+
+fn get(coord: &Coord, axis: usize) -> isize {
+    match axis {
+        0 => coord.0,
+        1 => coord.1,
+        _ => coord.2,
+    }
+}
+
+fn unit(axis: usize, length: isize) -> Coord {
+    match axis {
+        0 => Coord(length, 0, 0),
+        1 => Coord(0, length, 0),
+        _ => Coord(0, 0, length),
+    }
+}
+
+/// Axis of the normal and the two axes spanning the face in counter clockwise order.
+fn axes(normal: &Coord) -> (usize, usize, usize, bool) {
+    let (axis, positive) = match normal {
+        Coord(x, 0, 0) => (0, *x > 0),
+        Coord(0, y, 0) => (1, *y > 0),
+        Coord(0, 0, z) => (2, *z > 0),
+        _ => panic!("invalid normal {:?}", normal),
+    };
+
+    let (u, v) = ((axis + 1) % 3, (axis + 2) % 3);
+
+    if positive {
+        (axis, u, v, positive)
+    } else {
+        (axis, v, u, positive)
+    }
+}
+
+fn quad(
+    normal: Coord,
+    origin: Coord,
+    (u, width): (usize, isize),
+    (v, height): (usize, isize),
+) -> Quad {
+    let du = unit(u, width);
+    let dv = unit(v, height);
+
+    Quad {
+        normal,
+        corners: [origin, origin + du, origin + du + dv, origin + dv],
+    }
+}
+
+impl Mesh {
+    pub fn new(droplet: &Droplet, surface: Surface, merge: bool) -> Self {
+        let faces = droplet.exposed_faces(surface);
+
+        let quads = if merge {
+            Mesh::merge_faces(faces)
+        } else {
+            faces
+                .into_iter()
+                .map(|(cell, normal)| {
+                    let (axis, u, v, positive) = axes(&normal);
+                    let origin = cell + unit(axis, positive as isize);
+                    quad(normal, origin, (u, 1), (v, 1))
+                })
+                .collect()
+        };
+
+        Mesh { quads }
+    }
+
+    /// Greedily combines neighbouring faces in the same plane into larger rectangles.
+    fn merge_faces(faces: Vec<(Coord, Coord)>) -> Vec<Quad> {
+        let mut planes: HashMap<(Coord, isize), Vec<(isize, isize)>> = HashMap::new();
+
+        for (cell, normal) in faces {
+            let (axis, u, v, positive) = axes(&normal);
+            let plane = get(&cell, axis) + positive as isize;
+
+            planes
+                .entry((normal, plane))
+                .or_default()
+                .push((get(&cell, v), get(&cell, u)));
+        }
+
+        let mut planes: Vec<_> = planes.into_iter().collect();
+        planes.sort_unstable_by_key(|((normal, plane), _)| (normal.0, normal.1, normal.2, *plane));
+
+        let mut quads = vec![];
+
+        for ((normal, plane), mut cells) in planes {
+            let (axis, u, v, _) = axes(&normal);
+            cells.sort_unstable();
+
+            let mut open: HashSet<_> = cells.iter().copied().collect();
+
+            for (cv, cu) in cells {
+                if !open.contains(&(cv, cu)) {
+                    continue;
+                }
+
+                let mut width = 1;
+                while open.contains(&(cv, cu + width)) {
+                    width += 1;
+                }
+
+                let mut height = 1;
+                while (0..width).all(|w| open.contains(&(cv + height, cu + w))) {
+                    height += 1;
+                }
+
+                for h in 0..height {
+                    for w in 0..width {
+                        open.remove(&(cv + h, cu + w));
+                    }
+                }
+
+                let origin = unit(axis, plane) + unit(u, cu) + unit(v, cv);
+                quads.push(quad(normal, origin, (u, width), (v, height)));
+            }
+        }
+
+        quads
+    }
+
+    /// Wavefront OBJ with shared vertices and one quad per face.
+    pub fn to_obj(&self) -> String {
+        let mut vertices: HashMap<Coord, usize> = HashMap::new();
+        let mut output = String::new();
+        let mut faces = String::new();
+
+        for quad in &self.quads {
+            let indices: Vec<_> = quad
+                .corners
+                .iter()
+                .map(|corner| {
+                    let next = vertices.len() + 1;
+                    *vertices.entry(*corner).or_insert_with(|| {
+                        writeln!(output, "v {} {} {}", corner.0, corner.1, corner.2).unwrap();
+                        next
+                    })
+                })
+                .collect();
+
+            writeln!(
+                faces,
+                "f {} {} {} {}",
+                indices[0], indices[1], indices[2], indices[3]
+            )
+            .unwrap();
+        }
+
+        output + &faces
+    }
+
+    /// ASCII STL with every quad split into two triangles.
+    pub fn to_stl(&self, name: &str) -> String {
+        let mut output = format!("solid {name}\n");
+
+        for quad in &self.quads {
+            let [a, b, c, d] = quad.corners;
+
+            for triangle in [[a, b, c], [a, c, d]] {
+                let Coord(nx, ny, nz) = quad.normal;
+                writeln!(output, "  facet normal {nx} {ny} {nz}").unwrap();
+                writeln!(output, "    outer loop").unwrap();
+                for Coord(x, y, z) in triangle {
+                    writeln!(output, "      vertex {x} {y} {z}").unwrap();
+                }
+                writeln!(output, "    endloop").unwrap();
+                writeln!(output, "  endfacet").unwrap();
+            }
+        }
+
+        writeln!(output, "endsolid {name}").unwrap();
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Mesh;
+    use crate::droplet::{Coord, Droplet, Surface};
+
+    const TEST: &str = include_str!("test");
+
+    fn cross(a: Coord, b: Coord) -> Coord {
+        Coord(
+            a.1 * b.2 - a.2 * b.1,
+            a.2 * b.0 - a.0 * b.2,
+            a.0 * b.1 - a.1 * b.0,
+        )
+    }
+
+    #[test]
+    fn single_cube() {
+        let droplet: Droplet = "0,0,0".into();
+        let mesh = Mesh::new(&droplet, Surface::Total, false);
+
+        assert_eq!(mesh.quads.len(), 6);
+
+        let obj = mesh.to_obj();
+        assert_eq!(obj.lines().filter(|l| l.starts_with("v ")).count(), 8);
+        assert_eq!(obj.lines().filter(|l| l.starts_with("f ")).count(), 6);
+    }
+
+    #[test]
+    fn corners_wind_around_normal() {
+        let droplet: Droplet = TEST.into();
+        let mesh = Mesh::new(&droplet, Surface::Total, true);
+
+        for quad in mesh.quads {
+            let [a, b, _, d] = quad.corners;
+            let u = Coord(b.0 - a.0, b.1 - a.1, b.2 - a.2);
+            let v = Coord(d.0 - a.0, d.1 - a.1, d.2 - a.2);
+            let n = cross(u, v);
+
+            assert_eq!(
+                (n.0.signum(), n.1.signum(), n.2.signum()),
+                (quad.normal.0, quad.normal.1, quad.normal.2)
+            );
+        }
+    }
+
+    #[test]
+    fn merging_keeps_the_area() {
+        let droplet: Droplet = TEST.into();
+
+        for surface in [Surface::Total, Surface::Outer] {
+            let merged = Mesh::new(&droplet, surface, true);
+            let area: isize = merged
+                .quads
+                .iter()
+                .map(|quad| {
+                    let [a, _, c, _] = quad.corners;
+                    let diagonal = Coord(c.0 - a.0, c.1 - a.1, c.2 - a.2);
+                    [diagonal.0, diagonal.1, diagonal.2]
+                        .iter()
+                        .filter(|d| **d != 0)
+                        .product::<isize>()
+                        .abs()
+                })
+                .sum();
+
+            assert_eq!(area as usize, droplet.exposed_faces(surface).len());
+            assert!(merged.quads.len() < droplet.exposed_faces(surface).len());
+        }
+    }
+
+    #[test]
+    fn merged_bar() {
+        let droplet: Droplet = "0,0,0\n1,0,0\n2,0,0".into();
+        let mesh = Mesh::new(&droplet, Surface::Outer, true);
+
+        assert_eq!(mesh.quads.len(), 6);
+
+        let stl = mesh.to_stl("bar");
+        assert!(stl.starts_with("solid bar\n  facet normal "));
+        assert!(stl.ends_with("endsolid bar\n"));
+        assert_eq!(stl.matches("facet normal").count(), 12);
+    }
+}
@@ -0,0 +1,126 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::droplet::{Coord, NEIGHBOURS, ORTHO_NEIGHBOURS};
+
+/// Lowest and highest lava coordinate along every axis parallel line.
+struct LineExtents([HashMap<(isize, isize), (isize, isize)>; 3]);
+
+fn split(coord: &Coord, axis: usize) -> (isize, (isize, isize)) {
+    match axis {
+        0 => (coord.0, (coord.1, coord.2)),
+        1 => (coord.1, (coord.0, coord.2)),
+        _ => (coord.2, (coord.0, coord.1)),
+    }
+}
+
+impl LineExtents {
+    fn new(lava: &HashSet<Coord>) -> Self {
+        let mut extents: [HashMap<_, _>; 3] = Default::default();
+
+        for coord in lava {
+            for (axis, lines) in extents.iter_mut().enumerate() {
+                let (value, line) = split(coord, axis);
+                let extent = lines.entry(line).or_insert((value, value));
+                *extent = (extent.0.min(value), extent.1.max(value));
+            }
+        }
+
+        LineExtents(extents)
+    }
+
+    /// Whether a straight line from `coord` leaves the droplet without hitting lava.
+    fn escapes(&self, coord: &Coord) -> bool {
+        self.0.iter().enumerate().any(|(axis, lines)| {
+            let (value, line) = split(coord, axis);
+            lines
+                .get(&line)
+                .is_none_or(|(min, max)| value < *min || value > *max)
+        })
+    }
+}
+
+/// Finds all air cells that are enclosed by lava without visiting the whole bounding box.
+///
+/// Only the air around the lava is split into connected patches. Patches that can see past the
+/// droplet in a straight line are outside. The remaining ones are flood filled until they either
+/// touch a known outside patch or leave the bounding box `min..=max`, otherwise they are enclosed.
+pub fn find_enclosed_cells(lava: &HashSet<Coord>, min: Coord, max: Coord) -> HashSet<Coord> {
+    let shell: HashSet<_> = lava
+        .iter()
+        .flat_map(|c| NEIGHBOURS.iter().map(|n| *c + *n))
+        .filter(|c| !lava.contains(c))
+        .collect();
+
+    let extents = LineExtents::new(lava);
+    let mut outside: HashSet<Coord> = HashSet::new();
+    let mut undecided = vec![];
+    let mut visited = HashSet::new();
+
+    for start in &shell {
+        if !visited.insert(*start) {
+            continue;
+        }
+
+        let mut patch = vec![*start];
+        let mut stack = vec![*start];
+
+        while let Some(c) = stack.pop() {
+            for n in ORTHO_NEIGHBOURS.iter().map(|n| c + *n) {
+                if shell.contains(&n) && visited.insert(n) {
+                    patch.push(n);
+                    stack.push(n);
+                }
+            }
+        }
+
+        if patch.iter().any(|c| extents.escapes(c)) {
+            outside.extend(patch);
+        } else {
+            undecided.push(patch);
+        }
+    }
+
+    let in_box = |c: &Coord| {
+        c.0 > min.0 && c.1 > min.1 && c.2 > min.2 && c.0 < max.0 && c.1 < max.1 && c.2 < max.2
+    };
+
+    let mut inside = HashSet::new();
+
+    for patch in undecided {
+        if inside.contains(&patch[0]) || outside.contains(&patch[0]) {
+            continue;
+        }
+
+        let mut region: HashSet<_> = patch.iter().copied().collect();
+        let mut queue: VecDeque<_> = patch.into_iter().collect();
+        let mut escaped = false;
+
+        while let Some(c) = queue.pop_front() {
+            for n in ORTHO_NEIGHBOURS.iter().map(|n| c + *n) {
+                if lava.contains(&n) || region.contains(&n) {
+                    continue;
+                }
+
+                if outside.contains(&n) || !in_box(&n) {
+                    escaped = true;
+                    break;
+                }
+
+                region.insert(n);
+                queue.push_back(n);
+            }
+
+            if escaped {
+                break;
+            }
+        }
+
+        if escaped {
+            outside.extend(region.into_iter().filter(|c| shell.contains(c)));
+        } else {
+            inside.extend(region);
+        }
+    }
+
+    inside
+}
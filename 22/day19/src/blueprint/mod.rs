@@ -3,7 +3,7 @@ mod resources;
 
 use std::collections::HashMap;
 
-pub use resources::Resources;
+pub use resources::{Amount, Resources};
 
 pub use resource::Resource;
 
@@ -6,6 +6,34 @@ pub enum Resource {
     Geode,
 }
 
+impl Resource {
+    pub const ALL: [Resource; 4] = [
+        Resource::Ore,
+        Resource::Clay,
+        Resource::Obsidian,
+        Resource::Geode,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Resource::Ore => "ore",
+            Resource::Clay => "clay",
+            Resource::Obsidian => "obsidian",
+            Resource::Geode => "geode",
+        }
+    }
+
+    /// Name of the robot in the puzzle text, like `ore-collecting robot`.
+    pub fn robot(&self) -> &'static str {
+        match self {
+            Resource::Ore => "ore-collecting robot",
+            Resource::Clay => "clay-collecting robot",
+            Resource::Obsidian => "obsidian-collecting robot",
+            Resource::Geode => "geode-cracking robot",
+        }
+    }
+}
+
 impl From<&str> for Resource {
     fn from(value: &str) -> Self {
         match value.trim() {
@@ -1,49 +1,42 @@
-use std::{
-    collections::HashMap,
-    ops::{Add, AddAssign, Index, IndexMut, Sub, SubAssign},
-};
+use std::ops::{Add, AddAssign, Index, IndexMut, Sub, SubAssign};
 
 use super::resource::Resource;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// Count of a single resource, wide enough for the stock piling up over a long simulation.
+pub type Amount = u16;
+
+/// Amount of every resource, indexed by `Resource as usize`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
 pub struct Resources {
-    entries: HashMap<Resource, u8>,
+    entries: [Amount; 4],
 }
 
 impl Resources {
-    pub fn new(ore: u8, clay: u8, obsidian: u8, geode: u8) -> Self {
+    pub fn new(ore: Amount, clay: Amount, obsidian: Amount, geode: Amount) -> Self {
         Self {
-            entries: [
-                (Resource::Ore, ore),
-                (Resource::Clay, clay),
-                (Resource::Obsidian, obsidian),
-                (Resource::Geode, geode),
-            ]
-            .into(),
+            entries: [ore, clay, obsidian, geode],
         }
     }
 
-    pub fn max(mut self, other: &Resources) -> Self {
-        self.entries.iter_mut().for_each(|(r, c)| {
-            let other_amount = other.entries[r];
-            *c = other_amount.max(*c);
-        });
-
-        self
+    pub fn max(self, other: &Resources) -> Self {
+        Self {
+            entries: std::array::from_fn(|i| self.entries[i].max(other.entries[i])),
+        }
     }
 
-    pub fn iter(&self) -> impl Iterator<Item = (&Resource, &u8)> {
-        RESOURCE_ORDER
-            .iter()
-            .map(|r| (r, self.entries.get(r).expect("invalid resource found")))
+    /// Element-wise subtraction or `None` if any resource would drop below zero.
+    pub fn checked_sub(&self, rhs: &Resources) -> Option<Resources> {
+        if self.entries.iter().zip(&rhs.entries).any(|(s, r)| s < r) {
+            return None;
+        }
+
+        Some(Self {
+            entries: std::array::from_fn(|i| self.entries[i] - rhs.entries[i]),
+        })
     }
-}
 
-impl Default for Resources {
-    fn default() -> Self {
-        Self {
-            entries: DEFAULT_COSTS.into(),
-        }
+    pub fn iter(&self) -> impl Iterator<Item = (&Resource, &Amount)> {
+        Resource::ALL.iter().zip(self.entries.iter())
     }
 }
 
@@ -52,11 +45,11 @@ impl From<&str> for Resources {
         let mut cost: Resources = Default::default();
         for res in value.split(" and ") {
             let (amount, r_type) = res.split_once(" ").unwrap();
-            let amount = amount.parse::<u8>().unwrap();
+            let amount = amount.parse::<Amount>().unwrap();
 
             let r_type = Resource::from(r_type);
 
-            cost.entries.insert(r_type, amount);
+            cost[r_type] = amount;
         }
 
         cost
@@ -68,11 +61,7 @@ impl Add for &Resources {
 
     fn add(self, rhs: Self) -> Self::Output {
         Self::Output {
-            entries: self
-                .iter()
-                .zip(rhs.iter())
-                .map(|(s, rhs)| (*s.0, s.1 + rhs.1))
-                .collect(),
+            entries: std::array::from_fn(|i| self.entries[i] + rhs.entries[i]),
         }
     }
 }
@@ -81,79 +70,48 @@ impl Sub for &Resources {
     type Output = Resources;
 
     fn sub(self, rhs: Self) -> Self::Output {
-        Self::Output {
-            entries: self
-                .iter()
-                .zip(rhs.iter())
-                .map(|(s, rhs)| (*s.0, s.1.saturating_sub(*rhs.1)))
-                .collect(),
-        }
+        self.checked_sub(rhs)
+            .unwrap_or_else(|| panic!("can not subtract {rhs:?} from {self:?}"))
     }
 }
 
 impl AddAssign<&Resources> for Resources {
     fn add_assign(&mut self, rhs: &Resources) {
-        for resource in &RESOURCE_ORDER {
-            let target = self.entries.get_mut(resource).unwrap();
-            let rhs = rhs.entries.get(resource).unwrap();
-
-            *target += rhs;
-        }
+        *self = &*self + rhs;
     }
 }
 
 impl SubAssign<&Resources> for Resources {
     fn sub_assign(&mut self, rhs: &Resources) {
-        for resource in &RESOURCE_ORDER {
-            let target = self.entries.get_mut(resource).unwrap();
-            let rhs = rhs.entries.get(resource).unwrap();
-
-            *target -= rhs;
-        }
+        *self = &*self - rhs;
     }
 }
 
-const RESOURCE_ORDER: [Resource; 4] = [
-    Resource::Ore,
-    Resource::Clay,
-    Resource::Obsidian,
-    Resource::Geode,
-];
-
-const DEFAULT_COSTS: [(Resource, u8); 4] = [
-    (Resource::Ore, 0),
-    (Resource::Clay, 0),
-    (Resource::Obsidian, 0),
-    (Resource::Geode, 0),
-];
-
 impl Index<Resource> for Resources {
-    type Output = u8;
+    type Output = Amount;
 
     fn index(&self, index: Resource) -> &Self::Output {
-        &self.entries[&index]
+        &self.entries[index as usize]
     }
 }
 
 impl IndexMut<Resource> for Resources {
     fn index_mut(&mut self, index: Resource) -> &mut Self::Output {
-        self.entries.get_mut(&index).unwrap()
+        &mut self.entries[index as usize]
     }
 }
 
 impl Index<&Resource> for Resources {
-    type Output = u8;
+    type Output = Amount;
 
     fn index(&self, index: &Resource) -> &Self::Output {
-        self.entries.get(index).expect("got unexpected resource")
+        &self[*index]
     }
 }
 
 impl IndexMut<&Resource> for Resources {
     fn index_mut(&mut self, index: &Resource) -> &mut Self::Output {
-        self.entries
-            .get_mut(index)
-            .expect("got unexpected resource")
+        &mut self[*index]
     }
 }
 
@@ -165,28 +123,30 @@ mod tests {
 
     #[test]
     fn test_maximum() {
-        let cost_a = Resources {
-            entries: [
-                (Resource::Ore, 1),
-                (Resource::Clay, 2),
-                (Resource::Obsidian, 0),
-            ]
-            .into(),
-        };
-
-        let cost_b = Resources {
-            entries: [
-                (Resource::Ore, 0),
-                (Resource::Clay, 4),
-                (Resource::Obsidian, 3),
-            ]
-            .into(),
-        };
+        let cost_a = Resources::new(1, 2, 0, 0);
+        let cost_b = Resources::new(0, 4, 3, 0);
 
         let max = cost_a.max(&cost_b);
 
-        assert_eq!(max.entries[&Resource::Ore], 1);
-        assert_eq!(max.entries[&Resource::Clay], 4);
-        assert_eq!(max.entries[&Resource::Obsidian], 3);
+        assert_eq!(max[Resource::Ore], 1);
+        assert_eq!(max[Resource::Clay], 4);
+        assert_eq!(max[Resource::Obsidian], 3);
+    }
+
+    #[test]
+    fn test_checked_sub() {
+        let stock = Resources::new(4, 14, 0, 1);
+
+        assert_eq!(
+            stock.checked_sub(&Resources::new(3, 14, 0, 0)),
+            Some(Resources::new(1, 0, 0, 1))
+        );
+        assert_eq!(stock.checked_sub(&Resources::new(2, 0, 7, 0)), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_sub_underflow() {
+        let _ = &Resources::new(1, 0, 0, 0) - &Resources::new(2, 0, 0, 0);
     }
 }
@@ -0,0 +1,130 @@
+use std::{
+    num::NonZeroUsize,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+use crate::{
+    blueprint::{Amount, Blueprint},
+    simulation::{Pruning, Simulation},
+};
+
+/// Most geodes of every blueprint in `input` accepted by `filter` as `(id, geodes)`, sorted by id.
+///
+/// The filter gets the 0-based position of the blueprint's line besides the blueprint itself.
+///
+/// The blueprints are shared by a worker per available core. Every finished blueprint is reported
+/// on stderr.
+pub fn evaluate_blueprints<F>(
+    input: &str,
+    minutes: u8,
+    pruning: Pruning,
+    filter: F,
+) -> Vec<(u8, Amount)>
+where
+    F: Fn(usize, &Blueprint) -> bool,
+{
+    let blueprints: Vec<_> = input
+        .lines()
+        .map(Blueprint::from)
+        .enumerate()
+        .filter(|(position, blueprint)| filter(*position, blueprint))
+        .map(|(_, blueprint)| blueprint)
+        .collect();
+
+    let workers = thread::available_parallelism()
+        .map_or(1, NonZeroUsize::get)
+        .min(blueprints.len());
+
+    let next = AtomicUsize::new(0);
+    let finished = AtomicUsize::new(0);
+
+    let mut results: Vec<_> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut results = vec![];
+
+                    while let Some(blueprint) = blueprints.get(next.fetch_add(1, Ordering::Relaxed))
+                    {
+                        let simulation =
+                            Simulation::for_blueprint(blueprint.clone()).with_pruning(pruning);
+                        let geodes = simulation.simulate(minutes).geodes;
+
+                        let done = finished.fetch_add(1, Ordering::Relaxed) + 1;
+                        eprintln!(
+                            "[{done}/{}] blueprint {}: {geodes} geodes",
+                            blueprints.len(),
+                            blueprint.id
+                        );
+
+                        results.push((blueprint.id, geodes));
+                    }
+
+                    results
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect()
+    });
+
+    results.sort_unstable();
+    results
+}
+
+/// Sum of the quality levels of all blueprints.
+pub fn quality_sum(input: &str, minutes: u8, pruning: Pruning) -> u32 {
+    evaluate_blueprints(input, minutes, pruning, |_, _| true)
+        .into_iter()
+        .map(|(id, geodes)| id as u32 * geodes as u32)
+        .sum()
+}
+
+/// Product of the geodes of the first `count` blueprints.
+pub fn quality_prod(input: &str, minutes: u8, count: usize, pruning: Pruning) -> u32 {
+    evaluate_blueprints(input, minutes, pruning, |position, _| position < count)
+        .into_iter()
+        .map(|(_, geodes)| geodes as u32)
+        .product()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{evaluate_blueprints, quality_prod, quality_sum};
+    use crate::simulation::Pruning;
+
+    const TEST: &str = include_str!("test");
+
+    #[test]
+    fn test_quality_sum() {
+        assert_eq!(quality_sum(TEST, 24, Pruning::default()), 33);
+    }
+
+    #[test]
+    fn test_filter() {
+        let results = evaluate_blueprints(TEST, 24, Pruning::default(), |_, blueprint| {
+            blueprint.id == 2
+        });
+
+        assert_eq!(results, [(2, 12)]);
+    }
+
+    #[test]
+    fn test_empty_selection() {
+        assert!(evaluate_blueprints(TEST, 24, Pruning::default(), |_, _| false).is_empty());
+    }
+
+    #[test]
+    fn product_takes_blueprints_in_input_order() {
+        let reversed: Vec<_> = TEST.lines().rev().collect();
+
+        assert_eq!(
+            quality_prod(&reversed.join("\n"), 24, 1, Pruning::default()),
+            12
+        );
+    }
+}
@@ -1,64 +1,39 @@
-use std::thread;
+use std::{env, time::Instant};
 
-use simulation::Simulation;
+use evaluation::{quality_prod, quality_sum};
+use simulation::{Pruning, Simulation};
 
 mod blueprint;
+mod evaluation;
 mod simulation;
 
 const INPUT: &str = include_str!("input");
 
 fn main() {
-    let result = quality_sum(INPUT, 24, u8::MAX);
-
-    println!("part 1 {result}");
-
-    let result = quality_prod(INPUT, 32, 3);
-
-    println!("part 2 {result}")
-}
-
-fn quality_sum(input: &str, simulation_duration: u8, limit: u8) -> u32 {
-    let mut handles = vec![];
-    for line in input.lines().take(limit.into()) {
-        let simulation = Simulation::for_blueprint(line.into());
-
-        let result = thread::spawn(move || {
-            let result = simulation.simulate(simulation_duration) as u32;
-
-            simulation.blueprint.id as u32 * result
-        });
-
-        handles.push(result);
-    }
-
-    let mut quality_sum = 0;
-
-    for handle in handles {
-        quality_sum += handle.join().unwrap();
+    // e.g. `cargo run --release -- bound,memo` to compare pruning strategies, `report` prints the
+    // build order of the first blueprint
+    let args: Vec<_> = env::args().skip(1).collect();
+    let report = args.iter().any(|arg| arg == "report");
+    let pruning = args
+        .iter()
+        .find(|arg| *arg != "report")
+        .map(|arg| Pruning::from(arg.as_str()))
+        .unwrap_or_default();
+
+    if report {
+        let first =
+            Simulation::for_blueprint(INPUT.lines().next().unwrap().into()).with_pruning(pruning);
+        let order = first.simulate(24);
+        print!("{}", order.report(&first.blueprint, 24));
     }
 
-    quality_sum
-}
-
-fn quality_prod(input: &str, simulation_duration: u8, limit: u8) -> u32 {
-    let mut handles = vec![];
-    for line in input.lines().take(limit.into()) {
-        let simulation = Simulation::for_blueprint(line.into());
-
-        let result = thread::spawn(move || {
-            let result = simulation.simulate(simulation_duration) as u32;
+    let start = Instant::now();
+    let result = quality_sum(INPUT, 24, pruning);
 
-            result
-        });
+    println!("part 1 {result} ({:?})", start.elapsed());
 
-        handles.push(result);
-    }
-
-    let mut quality_prod = 1;
-
-    for handle in handles {
-        quality_prod *= handle.join().unwrap();
-    }
+    let start = Instant::now();
+    let result = quality_prod(INPUT, 32, 3, pruning);
 
-    quality_prod
+    println!("part 2 {result} ({:?})", start.elapsed());
 }
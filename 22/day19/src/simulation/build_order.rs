@@ -0,0 +1,193 @@
+use std::fmt::Write;
+
+use crate::blueprint::{Amount, Blueprint, Resource, Resources};
+
+/// Robots to buy as `(minute, robot)` to open `geodes` geodes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildOrder {
+    pub geodes: Amount,
+    pub purchases: Vec<(u8, Resource)>,
+}
+
+fn article(resource: Resource) -> &'static str {
+    match resource {
+        Resource::Ore | Resource::Obsidian => "an",
+        Resource::Clay | Resource::Geode => "a",
+    }
+}
+
+fn describe_costs(costs: &Resources) -> String {
+    let costs: Vec<_> = costs
+        .iter()
+        .filter(|(_, &amount)| amount > 0)
+        .map(|(r, amount)| format!("{amount} {}", r.name()))
+        .collect();
+
+    costs.join(" and ")
+}
+
+fn describe_collection(resource: Resource, count: Amount, total: Amount) -> String {
+    let plural = |amount: Amount| if amount == 1 { "" } else { "s" };
+
+    match resource {
+        Resource::Geode => format!(
+            "{count} geode-cracking robot{} crack{} {count} geode{}; you now have {total} open geode{}.",
+            plural(count),
+            if count == 1 { "s" } else { "" },
+            plural(count),
+            plural(total)
+        ),
+        _ => format!(
+            "{count} {}{} collect{} {count} {}; you now have {total} {}.",
+            resource.robot(),
+            plural(count),
+            if count == 1 { "s" } else { "" },
+            resource.name(),
+            resource.name()
+        ),
+    }
+}
+
+impl BuildOrder {
+    /// Minute by minute walkthrough in the format of the puzzle text.
+    ///
+    /// Panics if a robot is bought before the blueprint's costs can be paid.
+    pub fn report(&self, blueprint: &Blueprint, duration: u8) -> String {
+        let mut output = String::new();
+        let mut resources = Resources::default();
+        let mut population = Resources::new(1, 0, 0, 0);
+        let mut purchases = self.purchases.iter().peekable();
+
+        for minute in 1..=duration {
+            writeln!(output, "== Minute {minute} ==").unwrap();
+
+            let building = purchases
+                .next_if(|(at, _)| *at == minute)
+                .map(|(_, robot)| *robot);
+
+            if let Some(robot) = building {
+                let costs = &blueprint.bots[&robot];
+                resources = resources
+                    .checked_sub(costs)
+                    .unwrap_or_else(|| panic!("can not afford {robot:?} in minute {minute}"));
+
+                writeln!(
+                    output,
+                    "Spend {} to start building {} {}.",
+                    describe_costs(costs),
+                    article(robot),
+                    robot.robot()
+                )
+                .unwrap();
+            }
+
+            for resource in Resource::ALL {
+                let count = population[resource];
+                if count == 0 {
+                    continue;
+                }
+
+                resources[resource] += count;
+                writeln!(
+                    output,
+                    "{}",
+                    describe_collection(resource, count, resources[resource])
+                )
+                .unwrap();
+            }
+
+            if let Some(robot) = building {
+                population[robot] += 1;
+                writeln!(
+                    output,
+                    "The new {} is ready; you now have {} of them.",
+                    robot.robot(),
+                    population[robot]
+                )
+                .unwrap();
+            }
+
+            writeln!(output).unwrap();
+        }
+
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BuildOrder;
+    use crate::{
+        blueprint::{Blueprint, Resource},
+        simulation::Simulation,
+    };
+
+    const BLUEPRINT_1 :&str = "Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs 2 ore. Each obsidian robot costs 3 ore and 14 clay. Each geode robot costs 2 ore and 7 obsidian.";
+
+    fn example_order() -> BuildOrder {
+        BuildOrder {
+            geodes: 9,
+            purchases: vec![
+                (3, Resource::Clay),
+                (5, Resource::Clay),
+                (7, Resource::Clay),
+                (11, Resource::Obsidian),
+                (12, Resource::Clay),
+                (15, Resource::Obsidian),
+                (18, Resource::Geode),
+                (21, Resource::Geode),
+            ],
+        }
+    }
+
+    #[test]
+    fn report_matches_example() {
+        const MINUTE_3: &str = "== Minute 3 ==
+Spend 2 ore to start building a clay-collecting robot.
+1 ore-collecting robot collects 1 ore; you now have 1 ore.
+The new clay-collecting robot is ready; you now have 1 of them.
+";
+        const MINUTE_11: &str = "== Minute 11 ==
+Spend 3 ore and 14 clay to start building an obsidian-collecting robot.
+1 ore-collecting robot collects 1 ore; you now have 2 ore.
+3 clay-collecting robots collect 3 clay; you now have 4 clay.
+The new obsidian-collecting robot is ready; you now have 1 of them.
+";
+        const MINUTE_24: &str = "== Minute 24 ==
+1 ore-collecting robot collects 1 ore; you now have 6 ore.
+4 clay-collecting robots collect 4 clay; you now have 41 clay.
+2 obsidian-collecting robots collect 2 obsidian; you now have 8 obsidian.
+2 geode-cracking robots crack 2 geodes; you now have 9 open geodes.
+
+";
+        let blueprint = Blueprint::from(BLUEPRINT_1);
+
+        let report = example_order().report(&blueprint, 24);
+
+        assert!(report.contains(MINUTE_3));
+        assert!(report.contains(MINUTE_11));
+        assert!(report.ends_with(MINUTE_24));
+    }
+
+    #[test]
+    fn best_order_reaches_its_geodes() {
+        let blueprint = Blueprint::from(BLUEPRINT_1);
+        let order = Simulation::for_blueprint(blueprint.clone()).simulate(24);
+
+        let report = order.report(&blueprint, 24);
+
+        assert_eq!(order.geodes, 9);
+        assert!(report.ends_with("you now have 9 open geodes.\n\n"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn report_rejects_unaffordable_order() {
+        let order = BuildOrder {
+            geodes: 0,
+            purchases: vec![(1, Resource::Ore)],
+        };
+
+        order.report(&Blueprint::from(BLUEPRINT_1), 24);
+    }
+}
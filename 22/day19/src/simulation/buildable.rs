@@ -24,14 +24,14 @@ pub(super) fn can_build(
         .iter()
         .filter(|(_, &amount)| amount > 0)
         .map(|(r, amount)| {
+            // Nothing comes in without a bot collecting it
             if population[r] == 0 {
-                None
-            } else {
-                let remaining_resource = amount.saturating_sub(current_resources[r]);
-                let turns = remaining_resource / population[r];
-                let remainder = remaining_resource % population[r];
-                Some(if remainder == 0 { turns } else { turns + 1 })
+                return None;
             }
+
+            // A minute that only brings in part of the missing amount still has to be waited for
+            let missing = amount.saturating_sub(current_resources[r]);
+            Some(missing.div_ceil(population[r]))
         })
         .collect();
 
@@ -45,7 +45,8 @@ pub(super) fn can_build(
         .max()
         .expect("no None items should be present");
 
-    Buildable::In(max_turns)
+    // Waiting longer than any simulation can run is as good as never
+    u8::try_from(max_turns).map_or(Buildable::Never, Buildable::In)
 }
 
 #[cfg(test)]
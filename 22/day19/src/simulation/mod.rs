@@ -1,69 +1,138 @@
+mod build_order;
 mod buildable;
+mod pruning;
 
 use std::collections::HashMap;
 
-use crate::blueprint::{Blueprint, Resource, Resources};
+use crate::blueprint::{Amount, Blueprint, Resource, Resources};
 
 use self::buildable::{can_build, Buildable};
 
+pub use self::{build_order::BuildOrder, pruning::Pruning};
+
 #[derive(Debug, Clone)]
 pub struct Simulation {
     pub blueprint: Blueprint,
+    pub pruning: Pruning,
     max_population: Resources,
 }
 
+/// State shared by all branches of a single search.
+#[derive(Debug, Default)]
+struct Search {
+    duration: u8,
+    best: Amount,
+    memo: HashMap<(u8, Resources, Resources), Amount>,
+    /// Purchases on the way to the current branch
+    purchases: Vec<(u8, Resource)>,
+    /// Purchases of the branch that reached `best`
+    best_purchases: Vec<(u8, Resource)>,
+}
+
+/// Optimistic estimate of the geodes at the end of a branch.
+///
+/// Besides the geodes already open and those the current geode bots still crack, every remaining
+/// minute is assumed to finish one more geode bot for free. Those crack `t - 1 + t - 2 + ... + 0`
+/// geodes in the remaining `t` minutes, no real build order can do better.
+fn upper_bound(resources: &Resources, population: &Resources, remaining_time: u8) -> Amount {
+    let time = Amount::from(remaining_time);
+
+    resources[Resource::Geode]
+        + population[Resource::Geode] * time
+        + time * time.saturating_sub(1) / 2
+}
+
 impl Simulation {
     pub fn for_blueprint(blueprint: Blueprint) -> Self {
         let mut max_population = blueprint
             .bots
-            .iter()
-            .map(|(r, c)| c)
+            .values()
             .fold(Resources::default(), |a, e| a.max(e));
 
-        max_population[Resource::Geode] = u8::MAX;
+        max_population[Resource::Geode] = Amount::MAX;
         Self {
             blueprint,
+            pruning: Pruning::default(),
             max_population,
         }
     }
 
+    pub fn with_pruning(mut self, pruning: Pruning) -> Self {
+        self.pruning = pruning;
+        self
+    }
+
     fn simulation_step(
         &self,
+        search: &mut Search,
         resources: Resources,
         population: Resources,
         remaining_time: u8,
-    ) -> u8 {
+    ) -> Amount {
         if remaining_time == 0 {
             return resources[Resource::Geode];
         }
-        let mut max_geodes = resources[Resource::Geode];
-        for (bot_type, bot_costs) in &self.blueprint.bots {
+
+        let key = (remaining_time, resources, population);
+        if self.pruning.memo {
+            if let Some(&geodes) = search.memo.get(&key) {
+                return geodes;
+            }
+        }
+
+        // without building anything else the current geode bots keep collecting
+        let mut max_geodes =
+            resources[Resource::Geode] + population[Resource::Geode] * Amount::from(remaining_time);
+        if max_geodes > search.best {
+            search.best = max_geodes;
+            search.best_purchases = search.purchases.clone();
+        }
+
+        if self.pruning.upper_bound
+            && upper_bound(&resources, &population, remaining_time) <= search.best
+        {
+            return max_geodes;
+        }
+
+        let geode_now = self.pruning.greedy_geode
+            && can_build(
+                &resources,
+                &population,
+                &self.blueprint.bots[&Resource::Geode],
+            ) == Buildable::Now;
+
+        let minute = search.duration - remaining_time + 1;
+
+        for bot_type in Resource::ALL.iter().rev() {
             let pop_limit = self.max_population[bot_type];
-            if pop_limit == population[bot_type] {
+            if pop_limit == population[bot_type] || (geode_now && *bot_type != Resource::Geode) {
                 continue;
             }
 
+            let bot_costs = &self.blueprint.bots[bot_type];
+
             let buildable = can_build(&resources, &population, bot_costs);
             let result = match buildable {
                 Buildable::Never => continue,
                 Buildable::Now => {
                     // decrease resources
-                    let mut new_resources = resources.clone();
+                    let mut new_resources = resources;
                     new_resources -= bot_costs;
                     // get resources
                     new_resources += &population;
                     // add pop
-                    let mut new_population = population.clone();
+                    let mut new_population = population;
                     new_population[bot_type] += 1;
                     // recurse with one time less
-                    self.simulation_step(new_resources, new_population, remaining_time - 1)
+                    search.purchases.push((minute, *bot_type));
+                    self.simulation_step(search, new_resources, new_population, remaining_time - 1)
                 }
                 Buildable::In(x) => {
                     if x + 1 >= remaining_time {
                         continue;
                     }
                     // increase resources x times
-                    let mut new_resources = resources.clone();
+                    let mut new_resources = resources;
                     for _ in 0..=x {
                         new_resources += &population;
                     }
@@ -72,25 +141,45 @@ impl Simulation {
                     new_resources -= bot_costs;
 
                     // add population
-                    let mut new_population = population.clone();
+                    let mut new_population = population;
                     new_population[bot_type] += 1;
 
                     // recurse with x + 1 less minutes
-
-                    self.simulation_step(new_resources, new_population, remaining_time - (x + 1))
+                    search.purchases.push((minute + x, *bot_type));
+                    self.simulation_step(
+                        search,
+                        new_resources,
+                        new_population,
+                        remaining_time - (x + 1),
+                    )
                 }
             };
+            search.purchases.pop();
             max_geodes = max_geodes.max(result);
         }
 
+        if self.pruning.memo {
+            search.memo.insert(key, max_geodes);
+        }
+
         max_geodes
     }
 
-    pub fn simulate(&self, simulation_duration: u8) -> u8 {
+    /// Most geodes that can be opened within `simulation_duration` minutes and the robots to buy for them.
+    pub fn simulate(&self, simulation_duration: u8) -> BuildOrder {
         let resources = Resources::default();
         let population = Resources::new(1, 0, 0, 0);
 
-        self.simulation_step(resources, population, simulation_duration)
+        let mut search = Search {
+            duration: simulation_duration,
+            ..Default::default()
+        };
+        let geodes = self.simulation_step(&mut search, resources, population, simulation_duration);
+
+        BuildOrder {
+            geodes,
+            purchases: search.best_purchases,
+        }
     }
 }
 
@@ -100,11 +189,43 @@ mod tests {
 
     const BLUEPRINT_1 :&str = "Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs 2 ore. Each obsidian robot costs 3 ore and 14 clay. Each geode robot costs 2 ore and 7 obsidian.";
 
+    const BLUEPRINT_2: &str = "Blueprint 2: Each ore robot costs 2 ore. Each clay robot costs 3 ore. Each obsidian robot costs 3 ore and 8 clay. Each geode robot costs 3 ore and 12 obsidian.";
+
     #[test]
     fn test_simulation() {
         let simulation = Simulation::for_blueprint(BLUEPRINT_1.into());
 
-        let _result = simulation.simulate();
+        assert_eq!(simulation.simulate(24).geodes, 9);
+    }
+
+    #[test]
+    fn test_simulation_long() {
+        let simulation = Simulation::for_blueprint(BLUEPRINT_2.into());
+
+        assert_eq!(simulation.simulate(32).geodes, 62);
+    }
+
+    #[test]
+    fn pruning_strategies_agree() {
+        let strategies = [
+            Pruning::NONE,
+            Pruning::from("bound"),
+            Pruning::from("memo"),
+            Pruning::from("greedy"),
+            Pruning::ALL,
+        ];
+
+        for blueprint in [BLUEPRINT_1, BLUEPRINT_2] {
+            for pruning in strategies {
+                let simulation = Simulation::for_blueprint(blueprint.into()).with_pruning(pruning);
+
+                assert_eq!(
+                    simulation.simulate(24).geodes,
+                    if blueprint == BLUEPRINT_1 { 9 } else { 12 },
+                    "{pruning:?}"
+                );
+            }
+        }
     }
 
     #[test]
@@ -114,6 +235,6 @@ mod tests {
         assert_eq!(simulation.max_population[Resource::Ore], 4);
         assert_eq!(simulation.max_population[Resource::Clay], 14);
         assert_eq!(simulation.max_population[Resource::Obsidian], 7);
-        assert_eq!(simulation.max_population[Resource::Geode], u8::MAX);
+        assert_eq!(simulation.max_population[Resource::Geode], Amount::MAX);
     }
 }
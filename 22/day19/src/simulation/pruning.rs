@@ -0,0 +1,86 @@
+/// Ways to cut down the search tree. Every strategy can be toggled to compare its effect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pruning {
+    /// Skip branches whose optimistic geode estimate does not beat the best result so far.
+    ///
+    /// The estimate ignores the costs and pretends a new geode bot is ready after every remaining
+    /// minute, so it is never below what a branch can really reach and no better branch is cut.
+    pub upper_bound: bool,
+    /// Remember the result of every (time, resources, population) state.
+    pub memo: bool,
+    /// Build nothing but a geode bot in a minute where one can be afforded right away.
+    ///
+    /// Saving up for or building any other bot instead is never tried then. This is a heuristic and not guaranteed to find the best result for every blueprint.
+    pub greedy_geode: bool,
+}
+
+impl Pruning {
+    pub const NONE: Pruning = Pruning {
+        upper_bound: false,
+        memo: false,
+        greedy_geode: false,
+    };
+
+    pub const ALL: Pruning = Pruning {
+        upper_bound: true,
+        memo: true,
+        greedy_geode: true,
+    };
+}
+
+impl Default for Pruning {
+    fn default() -> Self {
+        // The bound alone is the fastest, the memo mostly costs time to hash the states
+        Pruning {
+            upper_bound: true,
+            memo: false,
+            greedy_geode: false,
+        }
+    }
+}
+
+impl From<&str> for Pruning {
+    /// Parses a comma separated list of strategies like `bound,memo,greedy`.
+    fn from(value: &str) -> Self {
+        let mut pruning = Pruning::NONE;
+
+        for strategy in value.split(',').map(str::trim) {
+            match strategy {
+                "" | "none" => {}
+                "bound" => pruning.upper_bound = true,
+                "memo" => pruning.memo = true,
+                "greedy" => pruning.greedy_geode = true,
+                "all" => pruning = Pruning::ALL,
+                x => panic!("Invalid pruning strategy {x}"),
+            }
+        }
+
+        pruning
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Pruning;
+
+    #[test]
+    fn parse_strategies() {
+        assert_eq!(Pruning::from("none"), Pruning::NONE);
+        assert_eq!(Pruning::from("all"), Pruning::ALL);
+        assert_eq!(Pruning::from("bound"), Pruning::default());
+        assert_eq!(
+            Pruning::from("bound, memo"),
+            Pruning {
+                greedy_geode: false,
+                ..Pruning::ALL
+            }
+        );
+        assert_eq!(
+            Pruning::from("greedy"),
+            Pruning {
+                greedy_geode: true,
+                ..Pruning::NONE
+            }
+        );
+    }
+}
@@ -1,66 +1,25 @@
-const INPUT: &str = include_str!("input");
-
-type Element = (isize, isize);
-type ElementVec = Vec<Element>;
-
-fn math_mod(mut value: isize, modulo: isize) -> isize {
-    if value >= modulo {
-        value % modulo
-    } else if value < 0 {
-        let factor = ((-value) / modulo) + 1;
-
-        value += factor * modulo;
-        value % modulo
-    } else {
-        value
-    }
-}
-
-fn move_element(list: &mut ElementVec, x: usize) {
-    let (idx, delta) = list[x];
-
-    if delta == 0 {
-        return;
-    }
-
-    let mut new_idx = math_mod(idx + delta, (list.len() - 1) as isize);
+use utils_22::CircularList;
 
-    if new_idx == 0 {
-        new_idx = (list.len() - 1) as isize;
-    }
-
-    list[x].0 = new_idx;
+#[cfg(test)]
+mod reference;
 
-    let range_start = idx.min(new_idx);
-    let range_end = idx.max(new_idx);
-    let range = range_start..=range_end;
-    let move_delta = if idx < new_idx { -1 } else { 1 };
-    for (list_index, (move_index, _delta)) in list.iter_mut().enumerate() {
-        if list_index == x {
-            continue;
-        }
-        if range.contains(move_index) {
-            *move_index += move_delta;
-        }
-    }
-}
+const INPUT: &str = include_str!("input");
 
-fn create_list(input: &str) -> ElementVec {
+fn create_list(input: &str) -> CircularList<isize> {
     input
         .split(&['\n', ','])
-        .enumerate()
-        .map(|(idx, v)| (idx as isize, v.trim().parse::<isize>().unwrap()))
+        .map(|v| v.trim().parse::<isize>().unwrap())
         .collect()
 }
 
-fn modify_list(list: &mut ElementVec, key: isize) {
-    list.iter_mut().for_each(|e| e.1 *= key);
+fn modify_list(list: &mut CircularList<isize>, key: isize) {
+    for handle in 0..list.len() {
+        list[handle] *= key;
+    }
 }
 
-fn get_result_list(mut list: ElementVec) -> Vec<isize> {
-    list.sort_by(|a, b| a.0.cmp(&b.0));
-
-    list.iter().map(|e| e.1).collect()
+fn get_result_list(list: &CircularList<isize>) -> Vec<isize> {
+    list.iter().copied().collect()
 }
 
 fn get_coordinates(coordinates: Vec<isize>) -> isize {
@@ -77,9 +36,10 @@ fn get_coordinates(coordinates: Vec<isize>) -> isize {
     a + b + c
 }
 
-fn do_mix(mut list: &mut ElementVec) {
-    for x in 0..list.len() {
-        move_element(&mut list, x);
+/// Moves every number by its value in the order of the input.
+fn do_mix(list: &mut CircularList<isize>) {
+    for handle in 0..list.len() {
+        list.move_by(handle, list[handle]);
     }
 }
 
@@ -88,7 +48,7 @@ fn main() {
 
     do_mix(&mut list);
 
-    let coordinates = get_coordinates(get_result_list(list));
+    let coordinates = get_coordinates(get_result_list(&list));
 
     println!("Part 1: {coordinates}");
 
@@ -99,7 +59,7 @@ fn main() {
         do_mix(&mut list);
     }
 
-    let coordinates = get_coordinates(get_result_list(list));
+    let coordinates = get_coordinates(get_result_list(&list));
 
     println!("Part 2: {coordinates}");
 }
@@ -107,29 +67,7 @@ fn main() {
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    #[test]
-    fn test_math_mod() {
-        assert_eq!(math_mod(-9000, 5000), 1000);
-        assert_eq!(math_mod(0, 5000), 0);
-        assert_eq!(math_mod(500, 5000), 500);
-        assert_eq!(math_mod(7000, 5000), 2000);
-        assert_eq!(math_mod(5000, 5000), 0);
-    }
-
-    #[test]
-    fn test_move() {
-        let mut list = create_list("4, 5, 6, 1, 7, 8, 9");
-        move_element(&mut list, 3);
-        assert_eq!(get_result_list(list), vec![4, 5, 6, 7, 1, 8, 9])
-    }
-
-    #[test]
-    fn test_move_negative() {
-        let mut list = create_list("4, -2, 5, 6, 7, 8, 9");
-        move_element(&mut list, 1);
-        assert_eq!(get_result_list(list), vec![4, 5, 6, 7, 8, -2, 9])
-    }
+    use crate::reference;
 
     #[test]
     fn test_function() {
@@ -139,17 +77,8 @@ mod tests {
 
         do_mix(&mut list);
 
-        assert_eq!(get_result_list(list), vec![1, 2, -3, 4, 0, 3, -2]);
-    }
-    #[test]
-    fn test_failing_step_in_testfile() {
-        let mut list = create_list("1, 2, -2, -3, 0, 3, 4");
-
-        move_element(&mut list, 2);
-
-        assert_eq!(get_result_list(list.clone()), vec![1, 2, -3, 0, 3, 4, -2]);
+        assert_eq!(get_result_list(&list), vec![1, 2, -3, 4, 0, 3, -2]);
     }
-
     #[test]
     fn coordinates_calculated_correctly() {
         const TEST: &str = include_str!("test");
@@ -158,7 +87,7 @@ mod tests {
 
         do_mix(&mut list);
 
-        let coordinate = get_coordinates(get_result_list(list));
+        let coordinate = get_coordinates(get_result_list(&list));
 
         assert_eq!(coordinate, 3);
     }
@@ -171,7 +100,7 @@ mod tests {
         modify_list(&mut list, 811589153);
 
         assert_eq!(
-            get_result_list(list),
+            get_result_list(&list),
             vec![
                 811589153,
                 1623178306,
@@ -192,7 +121,7 @@ mod tests {
 
         do_mix(&mut list);
         assert_eq!(
-            get_result_list(list.clone()),
+            get_result_list(&list),
             vec![
                 0,
                 -2434767459,
@@ -205,7 +134,7 @@ mod tests {
         );
         do_mix(&mut list);
         assert_eq!(
-            get_result_list(list.clone()),
+            get_result_list(&list),
             vec![
                 0,
                 2434767459,
@@ -218,7 +147,7 @@ mod tests {
         );
         do_mix(&mut list);
         assert_eq!(
-            get_result_list(list.clone()),
+            get_result_list(&list),
             vec![
                 0,
                 811589153,
@@ -231,7 +160,7 @@ mod tests {
         );
         do_mix(&mut list);
         assert_eq!(
-            get_result_list(list.clone()),
+            get_result_list(&list),
             vec![
                 0,
                 1623178306,
@@ -244,7 +173,7 @@ mod tests {
         );
         do_mix(&mut list);
         assert_eq!(
-            get_result_list(list.clone()),
+            get_result_list(&list),
             vec![
                 0,
                 811589153,
@@ -257,7 +186,7 @@ mod tests {
         );
         do_mix(&mut list);
         assert_eq!(
-            get_result_list(list.clone()),
+            get_result_list(&list),
             vec![
                 0,
                 811589153,
@@ -270,7 +199,7 @@ mod tests {
         );
         do_mix(&mut list);
         assert_eq!(
-            get_result_list(list.clone()),
+            get_result_list(&list),
             vec![
                 0,
                 -2434767459,
@@ -283,7 +212,7 @@ mod tests {
         );
         do_mix(&mut list);
         assert_eq!(
-            get_result_list(list.clone()),
+            get_result_list(&list),
             vec![
                 0,
                 1623178306,
@@ -296,7 +225,7 @@ mod tests {
         );
         do_mix(&mut list);
         assert_eq!(
-            get_result_list(list.clone()),
+            get_result_list(&list),
             vec![
                 0,
                 811589153,
@@ -309,7 +238,7 @@ mod tests {
         );
         do_mix(&mut list);
         assert_eq!(
-            get_result_list(list.clone()),
+            get_result_list(&list),
             vec![
                 0,
                 -2434767459,
@@ -333,8 +262,51 @@ mod tests {
             do_mix(&mut list);
         }
 
-        let coordinate = get_coordinates(get_result_list(list));
+        let coordinate = get_coordinates(get_result_list(&list));
 
         assert_eq!(coordinate, 1623178306);
     }
+
+    /// Small xorshift generator, the tests only need reproducible noise.
+    fn random_values(seed: &mut u64, len: usize) -> Vec<isize> {
+        (0..len)
+            .map(|_| {
+                *seed ^= *seed << 13;
+                *seed ^= *seed >> 7;
+                *seed ^= *seed << 17;
+                (*seed % 2001) as isize - 1000
+            })
+            .collect()
+    }
+
+    #[test]
+    fn matches_reference_mixing() {
+        let mut seed = 0x2022_1220;
+
+        for len in 2..60 {
+            let values = random_values(&mut seed, len);
+            let input: Vec<_> = values.iter().map(isize::to_string).collect();
+            let input = input.join(",");
+
+            let mut list = create_list(&input);
+            let mut expected = reference::create_list(&input);
+
+            // Large values wrap around many times
+            if len % 2 == 0 {
+                modify_list(&mut list, 811589153);
+                reference::modify_list(&mut expected, 811589153);
+            }
+
+            for _ in 0..3 {
+                do_mix(&mut list);
+                reference::do_mix(&mut expected);
+
+                assert_eq!(
+                    get_result_list(&list),
+                    reference::get_result_list(expected.clone()),
+                    "{input}"
+                );
+            }
+        }
+    }
 }
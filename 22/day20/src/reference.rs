@@ -0,0 +1,109 @@
+//! Original O(n²) mixing that shifts the index of every element after each move.
+//!
+//! Kept as a reference for the mixing on top of `CircularList`.
+
+pub type Element = (isize, isize);
+pub type ElementVec = Vec<Element>;
+
+pub fn math_mod(mut value: isize, modulo: isize) -> isize {
+    if value >= modulo {
+        value % modulo
+    } else if value < 0 {
+        let factor = ((-value) / modulo) + 1;
+
+        value += factor * modulo;
+        value % modulo
+    } else {
+        value
+    }
+}
+
+pub fn move_element(list: &mut ElementVec, x: usize) {
+    let (idx, delta) = list[x];
+
+    if delta == 0 {
+        return;
+    }
+
+    let mut new_idx = math_mod(idx + delta, (list.len() - 1) as isize);
+
+    if new_idx == 0 {
+        new_idx = (list.len() - 1) as isize;
+    }
+
+    list[x].0 = new_idx;
+
+    let range_start = idx.min(new_idx);
+    let range_end = idx.max(new_idx);
+    let range = range_start..=range_end;
+    let move_delta = if idx < new_idx { -1 } else { 1 };
+    for (list_index, (move_index, _delta)) in list.iter_mut().enumerate() {
+        if list_index == x {
+            continue;
+        }
+        if range.contains(move_index) {
+            *move_index += move_delta;
+        }
+    }
+}
+
+pub fn create_list(input: &str) -> ElementVec {
+    input
+        .split(&['\n', ','])
+        .enumerate()
+        .map(|(idx, v)| (idx as isize, v.trim().parse::<isize>().unwrap()))
+        .collect()
+}
+
+pub fn modify_list(list: &mut ElementVec, key: isize) {
+    list.iter_mut().for_each(|e| e.1 *= key);
+}
+
+pub fn get_result_list(mut list: ElementVec) -> Vec<isize> {
+    list.sort_by_key(|e| e.0);
+
+    list.iter().map(|e| e.1).collect()
+}
+
+pub fn do_mix(list: &mut ElementVec) {
+    for x in 0..list.len() {
+        move_element(list, x);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_math_mod() {
+        assert_eq!(math_mod(-9000, 5000), 1000);
+        assert_eq!(math_mod(0, 5000), 0);
+        assert_eq!(math_mod(500, 5000), 500);
+        assert_eq!(math_mod(7000, 5000), 2000);
+        assert_eq!(math_mod(5000, 5000), 0);
+    }
+
+    #[test]
+    fn test_move() {
+        let mut list = create_list("4, 5, 6, 1, 7, 8, 9");
+        move_element(&mut list, 3);
+        assert_eq!(get_result_list(list), vec![4, 5, 6, 7, 1, 8, 9])
+    }
+
+    #[test]
+    fn test_move_negative() {
+        let mut list = create_list("4, -2, 5, 6, 7, 8, 9");
+        move_element(&mut list, 1);
+        assert_eq!(get_result_list(list), vec![4, 5, 6, 7, 8, -2, 9])
+    }
+
+    #[test]
+    fn test_failing_step_in_testfile() {
+        let mut list = create_list("1, 2, -2, -3, 0, 3, 4");
+
+        move_element(&mut list, 2);
+
+        assert_eq!(get_result_list(list.clone()), vec![1, 2, -3, 0, 3, 4, -2]);
+    }
+}
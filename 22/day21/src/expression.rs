@@ -0,0 +1,271 @@
+use std::fmt::Display;
+
+use crate::{rational::Rational, Job, MonkeyMap, OpType};
+
+/// Expression tree of a monkey's job with one unknown number.
+///
+/// Sub-trees that do not depend on the variable are folded into constants while building.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Const(Rational),
+    Var(String),
+    Op(OpType, Box<Expr>, Box<Expr>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolveError {
+    /// Neither side depends on the variable.
+    NoVariable,
+    /// Both sides of the equation depend on the variable.
+    VariableOnBothSides,
+    /// The variable appears in both operands of an operation or as a divisor.
+    NonLinear,
+    /// Inverting the operation would divide by zero, so there is no unique solution.
+    DivisionByZero,
+}
+
+fn apply(op: OpType, a: Rational, b: Rational) -> Rational {
+    match op {
+        OpType::Add => a + b,
+        OpType::Sub => a - b,
+        OpType::Mul => a * b,
+        OpType::Div => a / b,
+    }
+}
+
+impl Expr {
+    /// Builds the job of `name`, treating the monkey `variable` as the unknown.
+    ///
+    /// Fails if folding the constants would divide by zero.
+    pub fn from_monkeys(
+        monkey_map: &MonkeyMap,
+        name: &str,
+        variable: &str,
+    ) -> Result<Self, SolveError> {
+        if name == variable {
+            return Ok(Expr::Var(variable.to_string()));
+        }
+
+        let expr = match &monkey_map[name].job {
+            Job::Literal(x) => Expr::Const(Rational::from(i128::from(*x))),
+            Job::Op(op, a, b) => {
+                let a = Expr::from_monkeys(monkey_map, a, variable)?;
+                let b = Expr::from_monkeys(monkey_map, b, variable)?;
+
+                match (op, a.constant(), b.constant()) {
+                    (OpType::Div, _, Some(b)) if b.is_zero() => {
+                        return Err(SolveError::DivisionByZero)
+                    }
+                    (_, Some(a), Some(b)) => Expr::Const(apply(*op, a, b)),
+                    _ => Expr::Op(*op, Box::new(a), Box::new(b)),
+                }
+            }
+        };
+
+        Ok(expr)
+    }
+
+    pub fn constant(&self) -> Option<Rational> {
+        match self {
+            Expr::Const(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Value of the variable for which this expression equals `target`.
+    ///
+    /// Every operation on the path to the variable is inverted, the other operand has to be a constant.
+    pub fn solve_for(&self, target: Rational) -> Result<Rational, SolveError> {
+        match self {
+            Expr::Const(_) => Err(SolveError::NoVariable),
+            Expr::Var(_) => Ok(target),
+            Expr::Op(op, a, b) => match (a.constant(), b.constant()) {
+                (None, None) => Err(SolveError::NonLinear),
+                (Some(_), Some(_)) => Err(SolveError::NoVariable),
+                // target = a op c
+                (None, Some(c)) => {
+                    let target = match op {
+                        OpType::Add => target - c,
+                        OpType::Sub => target + c,
+                        OpType::Mul if c.is_zero() => return Err(SolveError::DivisionByZero),
+                        OpType::Mul => target / c,
+                        OpType::Div if c.is_zero() => return Err(SolveError::DivisionByZero),
+                        OpType::Div => target * c,
+                    };
+                    a.solve_for(target)
+                }
+                // target = c op b
+                (Some(c), None) => {
+                    let target = match op {
+                        OpType::Add => target - c,
+                        OpType::Sub => c - target,
+                        OpType::Mul if c.is_zero() => return Err(SolveError::DivisionByZero),
+                        OpType::Mul => target / c,
+                        OpType::Div => return Err(SolveError::NonLinear),
+                    };
+                    b.solve_for(target)
+                }
+            },
+        }
+    }
+}
+
+impl Display for Expr {
+    /// Fully parenthesised infix notation.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Expr::Const(value) => write!(f, "{value}"),
+            Expr::Var(name) => write!(f, "{name}"),
+            Expr::Op(op, a, b) => {
+                let op = match op {
+                    OpType::Add => '+',
+                    OpType::Sub => '-',
+                    OpType::Mul => '*',
+                    OpType::Div => '/',
+                };
+                write!(f, "({a} {op} {b})")
+            }
+        }
+    }
+}
+
+/// Value of the variable for which both sides are equal.
+pub fn solve_equation(lhs: &Expr, rhs: &Expr) -> Result<Rational, SolveError> {
+    match (lhs.constant(), rhs.constant()) {
+        (None, None) => Err(SolveError::VariableOnBothSides),
+        (Some(_), Some(_)) => Err(SolveError::NoVariable),
+        (None, Some(target)) => lhs.solve_for(target),
+        (Some(target), None) => rhs.solve_for(target),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{solve_equation, Expr, SolveError};
+    use crate::{parser::monkey_map, rational::Rational, OpType};
+
+    const TEST: &str = include_str!("test");
+
+    fn var() -> Box<Expr> {
+        Box::new(Expr::Var("x".into()))
+    }
+
+    fn constant(value: i128) -> Box<Expr> {
+        Box::new(Expr::Const(value.into()))
+    }
+
+    #[test]
+    fn folds_constants() {
+        let monkey_map = monkey_map(TEST).unwrap();
+
+        let sjmn = Expr::from_monkeys(&monkey_map, "sjmn", "humn").unwrap();
+
+        assert_eq!(sjmn, Expr::Const(150.into()));
+    }
+
+    #[test]
+    fn solves_example() {
+        let monkey_map = monkey_map(TEST).unwrap();
+
+        let lhs = Expr::from_monkeys(&monkey_map, "pppw", "humn").unwrap();
+        let rhs = Expr::from_monkeys(&monkey_map, "sjmn", "humn").unwrap();
+
+        assert_eq!(lhs.to_string(), "((4 + (2 * (humn - 3))) / 4)");
+        assert_eq!(rhs.to_string(), "150");
+        assert_eq!(solve_equation(&lhs, &rhs), Ok(301.into()));
+        assert_eq!(solve_equation(&rhs, &lhs), Ok(301.into()));
+    }
+
+    #[test]
+    fn prints_root() {
+        let monkey_map = monkey_map(TEST).unwrap();
+
+        assert_eq!(
+            Expr::from_monkeys(&monkey_map, "root", "humn")
+                .unwrap()
+                .to_string(),
+            "(((4 + (2 * (humn - 3))) / 4) + 150)"
+        );
+        assert_eq!(
+            Expr::from_monkeys(&monkey_map, "root", "none")
+                .unwrap()
+                .to_string(),
+            "152"
+        );
+    }
+
+    #[test]
+    fn solves_exactly() {
+        // (7 - x) / 2 = 3 / 4
+        let lhs = Expr::Op(
+            OpType::Div,
+            Box::new(Expr::Op(OpType::Sub, constant(7), var())),
+            constant(2),
+        );
+
+        assert_eq!(
+            solve_equation(&lhs, &Expr::Const(Rational::new(3, 4))),
+            Ok(Rational::new(11, 2))
+        );
+    }
+
+    #[test]
+    fn keeps_large_literals_exact() {
+        // 2^53 + 1 is the first integer an f64 can not hold
+        let monkey_map =
+            monkey_map("root: humn + a\nhumn: 0\na: b - c\nb: 9007199254740993\nc: 0").unwrap();
+
+        let lhs = Expr::from_monkeys(&monkey_map, "humn", "humn").unwrap();
+        let rhs = Expr::from_monkeys(&monkey_map, "a", "humn").unwrap();
+
+        assert_eq!(
+            solve_equation(&lhs, &rhs),
+            Ok(Rational::from(9_007_199_254_740_993))
+        );
+    }
+
+    #[test]
+    fn reports_unsolvable_equations() {
+        let square = Expr::Op(OpType::Mul, var(), var());
+        let inverse = Expr::Op(OpType::Div, constant(1), var());
+        let zero = Expr::Op(OpType::Mul, var(), constant(0));
+
+        assert_eq!(
+            solve_equation(&var(), &var()),
+            Err(SolveError::VariableOnBothSides)
+        );
+        assert_eq!(
+            solve_equation(&square, &Expr::Const(4.into())),
+            Err(SolveError::NonLinear)
+        );
+        assert_eq!(
+            solve_equation(&inverse, &Expr::Const(4.into())),
+            Err(SolveError::NonLinear)
+        );
+        assert_eq!(
+            solve_equation(&zero, &Expr::Const(4.into())),
+            Err(SolveError::DivisionByZero)
+        );
+        assert_eq!(
+            solve_equation(
+                &Expr::Op(OpType::Div, var(), constant(0)),
+                &Expr::Const(4.into())
+            ),
+            Err(SolveError::DivisionByZero)
+        );
+        assert_eq!(
+            solve_equation(&Expr::Const(1.into()), &Expr::Const(4.into())),
+            Err(SolveError::NoVariable)
+        );
+    }
+
+    #[test]
+    fn reports_constant_division_by_zero() {
+        let monkey_map = monkey_map("root: humn + a\nhumn: 1\na: b / c\nb: 4\nc: 0").unwrap();
+
+        assert_eq!(
+            Expr::from_monkeys(&monkey_map, "root", "humn"),
+            Err(SolveError::DivisionByZero)
+        );
+    }
+}
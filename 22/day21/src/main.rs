@@ -1,5 +1,12 @@
+mod expression;
+mod parser;
+mod rational;
+
 use std::{cell::RefCell, collections::HashMap};
 
+use expression::{solve_equation, Expr};
+use parser::monkey_map;
+
 type MonkeyName = String;
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum OpType {
@@ -10,7 +17,7 @@ enum OpType {
 }
 #[derive(Debug, Clone, PartialEq)]
 enum Job {
-    Literal(f64),
+    Literal(i64),
     Op(OpType, MonkeyName, MonkeyName),
 }
 
@@ -23,45 +30,6 @@ struct Monkey {
 type MonkeyMap = HashMap<String, Monkey>;
 type MonkeyCache = RefCell<HashMap<String, f64>>;
 
-fn parse_monkeys(input: &str) -> Vec<Monkey> {
-    input
-        .lines()
-        .map(|l| {
-            let (name, job) = l.split_once(": ").unwrap();
-
-            if job.chars().next().unwrap().is_numeric() {
-                let literal = job.parse::<f64>().unwrap();
-                Monkey {
-                    name: name.into(),
-                    job: Job::Literal(literal),
-                }
-            } else {
-                let first = job[0..4].into();
-                let second = job[7..11].into();
-
-                let op = match &job[5..6] {
-                    "+" => OpType::Add,
-                    "-" => OpType::Sub,
-                    "*" => OpType::Mul,
-                    "/" => OpType::Div,
-                    x => panic!("invalid operation: '{x}'"),
-                };
-
-                Monkey {
-                    name: name.into(),
-                    job: Job::Op(op, first, second),
-                }
-            }
-        })
-        .collect()
-}
-
-fn monkey_map(input: &str) -> HashMap<String, Monkey> {
-    let monkeys = parse_monkeys(input);
-
-    monkeys.into_iter().map(|m| (m.name.clone(), m)).collect()
-}
-
 fn search_cache_or_recurse(
     monkey_map: &MonkeyMap,
     monkey_cache: Option<&MonkeyCache>,
@@ -72,7 +40,7 @@ fn search_cache_or_recurse(
             return (*result, false);
         }
     }
-    let (number, cacheable) = recursive_function(&monkey_map, monkey_cache, monkey_name);
+    let (number, cacheable) = recursive_function(monkey_map, monkey_cache, monkey_name);
 
     if cacheable {
         if let Some(cache) = monkey_cache {
@@ -91,10 +59,10 @@ fn recursive_function(
     let monkey = &monkey_map[monkey_name];
 
     match &monkey.job {
-        Job::Literal(x) => (*x, monkey_name != "humn"),
+        Job::Literal(x) => (*x as f64, monkey_name != "humn"),
         Job::Op(op, a, b) => {
-            let (a, cache_a) = search_cache_or_recurse(&monkey_map, monkey_cache, a);
-            let (b, cache_b) = search_cache_or_recurse(&monkey_map, monkey_cache, b);
+            let (a, cache_a) = search_cache_or_recurse(monkey_map, monkey_cache, a);
+            let (b, cache_b) = search_cache_or_recurse(monkey_map, monkey_cache, b);
 
             (
                 match op {
@@ -114,51 +82,37 @@ fn find_monkey_number(
     monkey_cache: Option<&MonkeyCache>,
     monkey_name: &String,
 ) -> f64 {
-    recursive_function(&monkey_map, monkey_cache, monkey_name).0
-}
-
-fn yell_number(monkey_map: &mut MonkeyMap, value: f64) {
-    let entry = monkey_map.get_mut("humn".into()).unwrap();
-    entry.job = Job::Literal(value);
+    recursive_function(monkey_map, monkey_cache, monkey_name).0
 }
 
 fn main() {
     const INPUT: &str = include_str!("input");
 
-    let mut monkey_map = monkey_map(INPUT);
+    let monkey_map = monkey_map(INPUT).unwrap_or_else(|err| panic!("invalid input: {err:?}"));
 
     let monkey_cache = RefCell::new(HashMap::new());
     let root_number = find_monkey_number(&monkey_map, Some(&monkey_cache), &"root".into());
 
     println!("Part 1 {root_number}");
 
-    let root = monkey_map["root".into()].clone();
-
-    let mut range = (3000000000000f64, 4000000000000f64);
-    // first is too large, second too small
-    if let Job::Op(_, a, b) = root.job {
-        loop {
-            let middle = (range.1 + range.0) / 2.0;
-
-            yell_number(&mut monkey_map, middle);
-
-            let a = find_monkey_number(&monkey_map, Some(&monkey_cache), &a);
-            let b = find_monkey_number(&monkey_map, Some(&monkey_cache), &b);
-
-            let diff = a - b;
-
-            if diff > 0.0 {
-                range = (middle, range.1);
-            } else if diff < 0.0 {
-                range = (range.0, middle);
-            } else {
-                println!("Part 2 {middle}");
-                break;
-            }
-        }
-    } else {
+    let Job::Op(_, a, b) = monkey_map["root"].job.clone() else {
         panic!("root has wrong job");
-    }
+    };
+
+    let build = |name: &str| {
+        Expr::from_monkeys(&monkey_map, name, "humn")
+            .unwrap_or_else(|err| panic!("can not build the job of {name}: {err:?}"))
+    };
+    let lhs = build(&a);
+    let rhs = build(&b);
+    println!("root: {lhs} = {rhs}");
+
+    let humn = solve_equation(&lhs, &rhs)
+        .unwrap_or_else(|err| panic!("can not solve for humn: {err:?}"))
+        .to_integer()
+        .expect("humn has to yell a whole number");
+
+    println!("Part 2 {humn}");
 }
 
 #[cfg(test)]
@@ -168,16 +122,21 @@ mod tests {
 
     const TEST: &str = include_str!("test");
 
+    fn yell_number(monkey_map: &mut MonkeyMap, value: i64) {
+        let entry = monkey_map.get_mut("humn").unwrap();
+        entry.job = Job::Literal(value);
+    }
+
     #[test]
     fn parse_works() {
-        let monkeys = parse_monkeys(TEST);
+        let monkeys = parser::parse_monkeys(TEST).unwrap();
 
         assert_eq!(monkeys.len(), 15);
     }
 
     #[test]
     fn literal_works() {
-        let monkey_map = monkey_map(TEST);
+        let monkey_map = monkey_map(TEST).unwrap();
 
         let number = find_monkey_number(&monkey_map, None, &"dbpl".into());
         assert_eq!(number, 5f64);
@@ -191,7 +150,7 @@ mod tests {
 
     #[test]
     fn simple_op_works() {
-        let monkey_map = monkey_map(TEST);
+        let monkey_map = monkey_map(TEST).unwrap();
         let number = find_monkey_number(&monkey_map, None, &"drzm".into());
 
         assert_eq!(number, 30f64);
@@ -199,7 +158,7 @@ mod tests {
 
     #[test]
     fn test_scenario_works() {
-        let monkey_map = monkey_map(TEST);
+        let monkey_map = monkey_map(TEST).unwrap();
 
         let number = find_monkey_number(&monkey_map, None, &"root".into());
 
@@ -208,12 +167,12 @@ mod tests {
 
     #[test]
     fn test_part_2() {
-        let mut monkey_map = monkey_map(TEST);
+        let mut monkey_map = monkey_map(TEST).unwrap();
 
-        let root = &monkey_map["root".into()].clone();
+        let root = &monkey_map["root"].clone();
 
         if let Job::Op(_, a, b) = &root.job {
-            yell_number(&mut monkey_map, 301f64);
+            yell_number(&mut monkey_map, 301);
 
             assert_eq!(
                 find_monkey_number(&monkey_map, None, a),
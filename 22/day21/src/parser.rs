@@ -0,0 +1,250 @@
+use std::collections::HashMap;
+
+use crate::{Job, Monkey, MonkeyMap, OpType};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// Character that can not start a token, with its 1-based line.
+    InvalidCharacter {
+        line: usize,
+        character: char,
+    },
+    /// Line that is not of the form `name: number` or `name: name op name`.
+    InvalidJob {
+        line: usize,
+    },
+    DuplicateMonkey(String),
+    UndefinedMonkey {
+        name: String,
+        referenced_by: String,
+    },
+    /// Monkeys that wait on each other, the first one is repeated at the end.
+    Cycle(Vec<String>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Name(String),
+    Number(i64),
+    Op(OpType),
+    Colon,
+}
+
+fn tokenize(line: &str, line_number: usize) -> Result<Vec<Token>, ParseError> {
+    let mut tokens = vec![];
+    let mut chars = line.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        let token = match c {
+            c if c.is_whitespace() => {
+                chars.next();
+                continue;
+            }
+            ':' => Token::Colon,
+            '+' => Token::Op(OpType::Add),
+            '-' => Token::Op(OpType::Sub),
+            '*' => Token::Op(OpType::Mul),
+            '/' => Token::Op(OpType::Div),
+            c if c.is_ascii_digit() => {
+                let mut number = String::new();
+                while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+                    number.push(digit);
+                }
+
+                let number = number
+                    .parse()
+                    .map_err(|_| ParseError::InvalidJob { line: line_number })?;
+                tokens.push(Token::Number(number));
+                continue;
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut name = String::new();
+                while let Some(c) = chars.next_if(|c| c.is_alphanumeric() || *c == '_') {
+                    name.push(c);
+                }
+
+                tokens.push(Token::Name(name));
+                continue;
+            }
+            character => {
+                return Err(ParseError::InvalidCharacter {
+                    line: line_number,
+                    character,
+                })
+            }
+        };
+
+        chars.next();
+        tokens.push(token);
+    }
+
+    Ok(tokens)
+}
+
+fn parse_monkey(line: &str, line_number: usize) -> Result<Monkey, ParseError> {
+    match tokenize(line, line_number)?.as_slice() {
+        [Token::Name(name), Token::Colon, job @ ..] => {
+            let job = match job {
+                [Token::Number(x)] => Job::Literal(*x),
+                [Token::Op(OpType::Sub), Token::Number(x)] => Job::Literal(-*x),
+                [Token::Name(a), Token::Op(op), Token::Name(b)] => {
+                    Job::Op(*op, a.clone(), b.clone())
+                }
+                _ => return Err(ParseError::InvalidJob { line: line_number }),
+            };
+
+            Ok(Monkey {
+                name: name.clone(),
+                job,
+            })
+        }
+        _ => Err(ParseError::InvalidJob { line: line_number }),
+    }
+}
+
+/// Parses one monkey per non-empty line.
+pub fn parse_monkeys(input: &str) -> Result<Vec<Monkey>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| parse_monkey(line, i + 1))
+        .collect()
+}
+
+fn find_cycle(monkey_map: &MonkeyMap) -> Option<Vec<String>> {
+    fn visit<'a>(
+        monkey_map: &'a MonkeyMap,
+        name: &'a str,
+        done: &mut HashMap<&'a str, bool>,
+        path: &mut Vec<&'a str>,
+    ) -> Option<Vec<String>> {
+        match done.get(name) {
+            Some(true) => return None,
+            Some(false) => {
+                let start = path.iter().position(|n| *n == name).unwrap();
+                let mut cycle: Vec<_> = path[start..].iter().map(|n| n.to_string()).collect();
+                cycle.push(name.to_string());
+                return Some(cycle);
+            }
+            None => {}
+        }
+
+        done.insert(name, false);
+        path.push(name);
+
+        if let Job::Op(_, a, b) = &monkey_map[name].job {
+            for next in [a, b] {
+                if let Some(cycle) = visit(monkey_map, next, done, path) {
+                    return Some(cycle);
+                }
+            }
+        }
+
+        path.pop();
+        done.insert(name, true);
+        None
+    }
+
+    let mut names: Vec<_> = monkey_map.keys().collect();
+    names.sort_unstable();
+
+    let mut done = HashMap::new();
+    names
+        .into_iter()
+        .find_map(|name| visit(monkey_map, name, &mut done, &mut vec![]))
+}
+
+/// Parses all monkeys and checks that every job can be evaluated.
+pub fn monkey_map(input: &str) -> Result<MonkeyMap, ParseError> {
+    let mut monkey_map = HashMap::new();
+
+    for monkey in parse_monkeys(input)? {
+        if monkey_map.contains_key(&monkey.name) {
+            return Err(ParseError::DuplicateMonkey(monkey.name));
+        }
+        monkey_map.insert(monkey.name.clone(), monkey);
+    }
+
+    let mut monkeys: Vec<_> = monkey_map.values().collect();
+    monkeys.sort_unstable_by_key(|monkey| &monkey.name);
+
+    for monkey in monkeys {
+        if let Job::Op(_, a, b) = &monkey.job {
+            if let Some(name) = [a, b].into_iter().find(|n| !monkey_map.contains_key(*n)) {
+                return Err(ParseError::UndefinedMonkey {
+                    name: name.clone(),
+                    referenced_by: monkey.name.clone(),
+                });
+            }
+        }
+    }
+
+    match find_cycle(&monkey_map) {
+        Some(cycle) => Err(ParseError::Cycle(cycle)),
+        None => Ok(monkey_map),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{monkey_map, parse_monkeys, ParseError};
+    use crate::{Job, OpType};
+
+    #[test]
+    fn any_names_and_whitespace() {
+        let monkeys =
+            parse_monkeys("root_monkey :a+  longer_name\n\n  a: 12\nlonger_name:-3").unwrap();
+
+        assert_eq!(monkeys.len(), 3);
+        assert_eq!(
+            monkeys[0].job,
+            Job::Op(OpType::Add, "a".into(), "longer_name".into())
+        );
+        assert_eq!(monkeys[0].name, "root_monkey");
+        assert_eq!(monkeys[1].job, Job::Literal(12));
+        assert_eq!(monkeys[2].job, Job::Literal(-3));
+    }
+
+    #[test]
+    fn invalid_lines() {
+        assert_eq!(
+            parse_monkeys("root: a + b\nabc: 1 %").unwrap_err(),
+            ParseError::InvalidCharacter {
+                line: 2,
+                character: '%'
+            }
+        );
+        assert_eq!(
+            parse_monkeys("root: a +").unwrap_err(),
+            ParseError::InvalidJob { line: 1 }
+        );
+        assert_eq!(
+            parse_monkeys("12: 3").unwrap_err(),
+            ParseError::InvalidJob { line: 1 }
+        );
+    }
+
+    #[test]
+    fn undefined_and_duplicate_monkeys() {
+        assert_eq!(
+            monkey_map("root: a * b\na: 3").unwrap_err(),
+            ParseError::UndefinedMonkey {
+                name: "b".into(),
+                referenced_by: "root".into()
+            }
+        );
+        assert_eq!(
+            monkey_map("a: 1\na: 2").unwrap_err(),
+            ParseError::DuplicateMonkey("a".into())
+        );
+    }
+
+    #[test]
+    fn cycles() {
+        assert_eq!(
+            monkey_map("root: a + c\na: b * c\nb: a - c\nc: 4").unwrap_err(),
+            ParseError::Cycle(vec!["a".into(), "b".into(), "a".into()])
+        );
+    }
+}
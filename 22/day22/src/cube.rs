@@ -0,0 +1,343 @@
+use std::collections::VecDeque;
+
+use crate::{Cell, Orientation, Position, Row};
+
+type Vec3 = [isize; 3];
+
+fn neg(v: Vec3) -> Vec3 {
+    [-v[0], -v[1], -v[2]]
+}
+
+fn dot(a: Vec3, b: Vec3) -> isize {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn add(a: Vec3, b: Vec3) -> Vec3 {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+fn scale(v: Vec3, factor: isize) -> Vec3 {
+    [v[0] * factor, v[1] * factor, v[2] * factor]
+}
+
+/// Face of the folded cube, `right` and `down` follow the columns and rows of the map.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Face {
+    /// Top left cell of the face on the map
+    pub row: isize,
+    pub col: isize,
+    pub right: Vec3,
+    pub down: Vec3,
+    pub normal: Vec3,
+}
+
+impl Face {
+    fn direction(&self, orientation: Orientation) -> Vec3 {
+        match orientation {
+            Orientation::Up => neg(self.down),
+            Orientation::Right => self.right,
+            Orientation::Down => self.down,
+            Orientation::Left => neg(self.right),
+        }
+    }
+
+    fn orientation(&self, direction: Vec3) -> Orientation {
+        [
+            Orientation::Up,
+            Orientation::Right,
+            Orientation::Down,
+            Orientation::Left,
+        ]
+        .into_iter()
+        .find(|o| self.direction(*o) == direction)
+        .expect("direction is not parallel to the face")
+    }
+
+    /// Face on the other side of the edge in `orientation` when folding along it.
+    fn fold(&self, orientation: Orientation, size: isize) -> Face {
+        let (row, col) = match orientation {
+            Orientation::Up => (self.row - size, self.col),
+            Orientation::Right => (self.row, self.col + size),
+            Orientation::Down => (self.row + size, self.col),
+            Orientation::Left => (self.row, self.col - size),
+        };
+
+        let (right, down) = match orientation {
+            Orientation::Up => (self.right, self.normal),
+            Orientation::Right => (neg(self.normal), self.down),
+            Orientation::Down => (self.right, neg(self.normal)),
+            Orientation::Left => (self.normal, self.down),
+        };
+
+        Face {
+            row,
+            col,
+            right,
+            down,
+            normal: self.direction(orientation),
+        }
+    }
+}
+
+/// Flat net folded into a cube, used to find where a step over the edge of a face ends up.
+///
+/// Cells are placed in doubled coordinates around the cube's centre, so a cell centre is at
+/// `2 * local - (size - 1)` along the face axes and the face itself at `size` along its normal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CubeNet {
+    pub face_size: isize,
+    pub faces: Vec<Face>,
+}
+
+impl CubeNet {
+    /// Finds the six faces of the map and folds them around the first one.
+    pub fn fold(rows: &[Row]) -> Self {
+        let cells = rows.iter().flatten().filter(|c| **c != Cell::Void).count();
+        let face_size = ((cells / 6) as f64).sqrt().round() as isize;
+
+        assert!(
+            face_size > 0 && 6 * face_size * face_size == cells as isize,
+            "{cells} cells can not form a cube"
+        );
+
+        let is_face = |row: isize, col: isize| {
+            row >= 0
+                && col >= 0
+                && rows
+                    .get(row as usize)
+                    .and_then(|r| r.get(col as usize))
+                    .is_some_and(|cell| *cell != Cell::Void)
+        };
+
+        let first_col = rows[0]
+            .iter()
+            .position(|cell| *cell != Cell::Void)
+            .expect("first row is empty") as isize;
+
+        let mut faces = vec![Face {
+            row: 0,
+            col: first_col,
+            right: [1, 0, 0],
+            down: [0, 1, 0],
+            normal: [0, 0, 1],
+        }];
+        let mut queue = VecDeque::from([0]);
+
+        while let Some(current) = queue.pop_front() {
+            for orientation in [
+                Orientation::Up,
+                Orientation::Right,
+                Orientation::Down,
+                Orientation::Left,
+            ] {
+                let next = faces[current].fold(orientation, face_size);
+
+                if is_face(next.row, next.col)
+                    && !faces.iter().any(|f| (f.row, f.col) == (next.row, next.col))
+                {
+                    assert!(
+                        faces.iter().all(|f| f.normal != next.normal),
+                        "faces overlap when folding the net"
+                    );
+
+                    faces.push(next);
+                    queue.push_back(faces.len() - 1);
+                }
+            }
+        }
+
+        assert_eq!(faces.len(), 6, "net is not connected or has extra faces");
+        faces.sort_unstable_by_key(|f| (f.row, f.col));
+
+        CubeNet { face_size, faces }
+    }
+
+    /// Index of the face containing the cell, faces are sorted in reading order.
+    pub fn face_index(&self, row: isize, col: isize) -> Option<usize> {
+        self.faces.iter().position(|f| {
+            (f.row..f.row + self.face_size).contains(&row)
+                && (f.col..f.col + self.face_size).contains(&col)
+        })
+    }
+
+    fn face_at(&self, row: isize, col: isize) -> &Face {
+        let index = self
+            .face_index(row, col)
+            .unwrap_or_else(|| panic!("({row}, {col}) is not on a face"));
+
+        &self.faces[index]
+    }
+
+    /// Index of the face glued to the edge of `face` in `orientation`.
+    pub fn neighbour(&self, face: usize, orientation: Orientation) -> usize {
+        let direction = self.faces[face].direction(orientation);
+
+        self.faces
+            .iter()
+            .position(|f| f.normal == direction)
+            .expect("every side of the cube has a face")
+    }
+
+    /// Position after stepping over the edge of the current face onto the adjacent one.
+    pub fn wrap(&self, position: Position) -> Position {
+        let size = self.face_size;
+        let face = self.face_at(position.0, position.1);
+
+        let u = 2 * (position.1 - face.col) - (size - 1);
+        let v = 2 * (position.0 - face.row) - (size - 1);
+        let point = add(
+            add(scale(face.right, u), scale(face.down, v)),
+            scale(face.normal, size),
+        );
+
+        // Out of the old face along the walking direction and half a cell into the new one
+        let direction = face.direction(position.2);
+        let point = add(add(point, direction), neg(face.normal));
+
+        let next = self
+            .faces
+            .iter()
+            .find(|f| f.normal == direction)
+            .expect("every side of the cube has a face");
+
+        let col = (dot(point, next.right) + size - 1) / 2;
+        let row = (dot(point, next.down) + size - 1) / 2;
+
+        (
+            next.row + row,
+            next.col + col,
+            next.orientation(neg(face.normal)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CubeNet;
+    use crate::{Map, Orientation, Position};
+
+    /// All eleven nets of a cube, every `#` is a face.
+    const NETS: [&str; 11] = [
+        "#...\n####\n#...",
+        "#...\n####\n.#..",
+        "#...\n####\n..#.",
+        "#...\n####\n...#",
+        ".#..\n####\n.#..",
+        ".#..\n####\n..#.",
+        "##..\n.###\n.#..",
+        "##..\n.###\n..#.",
+        "##..\n.###\n...#",
+        "##..\n.##.\n..##",
+        "###..\n..###",
+    ];
+
+    /// Open map of a net where every face has `size` cells per side.
+    fn expand(net: &str, size: usize) -> String {
+        net.lines()
+            .flat_map(|line| {
+                let row: String = line
+                    .chars()
+                    .flat_map(|c| std::iter::repeat_n(if c == '#' { '.' } else { ' ' }, size))
+                    .collect();
+                std::iter::repeat_n(row, size)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn turn_around(position: Position) -> Position {
+        let orientation = match position.2 {
+            Orientation::Up => Orientation::Down,
+            Orientation::Right => Orientation::Left,
+            Orientation::Down => Orientation::Up,
+            Orientation::Left => Orientation::Right,
+        };
+
+        (position.0, position.1, orientation)
+    }
+
+    /// Every position on the border of a face that faces out of it.
+    fn edge_positions(net: &CubeNet) -> Vec<Position> {
+        let last = net.face_size - 1;
+
+        net.faces
+            .iter()
+            .flat_map(|face| {
+                (0..net.face_size).flat_map(move |i| {
+                    [
+                        (face.row, face.col + i, Orientation::Up),
+                        (face.row + i, face.col + last, Orientation::Right),
+                        (face.row + last, face.col + i, Orientation::Down),
+                        (face.row + i, face.col, Orientation::Left),
+                    ]
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn wrapping_back_returns() {
+        for net in NETS {
+            let map = Map::cube(&expand(net, 3));
+            let cube = map.net.as_ref().unwrap();
+
+            for position in edge_positions(cube) {
+                let wrapped = cube.wrap(position);
+
+                assert_eq!(
+                    cube.wrap(turn_around(wrapped)),
+                    turn_around(position),
+                    "{net}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn input_wraps_back() {
+        const INPUT: &str = include_str!("input");
+        let map = Map::cube(INPUT.split_once("\n\n").unwrap().0);
+        let cube = map.net.as_ref().unwrap();
+
+        assert_eq!(cube.face_size, 50);
+        for position in edge_positions(cube) {
+            assert_eq!(
+                cube.wrap(turn_around(cube.wrap(position))),
+                turn_around(position)
+            );
+        }
+    }
+
+    #[test]
+    fn walking_around_the_cube() {
+        for net in NETS {
+            let map = Map::cube(&expand(net, 3));
+
+            for position in edge_positions(map.net.as_ref().unwrap()) {
+                assert_eq!(map.do_move(position, 12), position, "{net}");
+            }
+        }
+    }
+
+    #[test]
+    fn sample_edges() {
+        const TEST: &str = include_str!("test");
+        let map = Map::cube(TEST.split_once("\n\n").unwrap().0);
+        let cube = map.net.as_ref().unwrap();
+
+        assert_eq!(cube.face_size, 4);
+        // The examples from the puzzle text
+        assert_eq!(
+            cube.wrap((5, 11, Orientation::Right)),
+            (8, 14, Orientation::Down)
+        );
+        assert_eq!(
+            cube.wrap((11, 10, Orientation::Down)),
+            (7, 1, Orientation::Up)
+        );
+        assert_eq!(
+            cube.wrap((4, 6, Orientation::Up)),
+            (2, 8, Orientation::Right)
+        );
+    }
+}
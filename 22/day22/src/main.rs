@@ -1,3 +1,11 @@
+mod cube;
+mod render;
+
+use std::env;
+
+use cube::CubeNet;
+use render::{render_cube, render_trail};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Cell {
     Open,
@@ -12,26 +20,16 @@ struct Map {
     rows: Vec<Row>,
     width: isize,
     height: isize,
-    face_size: isize,
-    cube: bool,
+    /// Folded cube for the cube wrapping or `None` to wrap around the flat map
+    net: Option<CubeNet>,
 }
 
 impl Map {
     fn cube(input: &str) -> Self {
-        let rows = Self::rows_from_input(input);
-
-        let height = rows.len() as isize;
-        let width = rows[0].len() as isize;
+        let mut map = Self::flat(input);
+        map.net = Some(CubeNet::fold(&map.rows));
 
-        let face_size = width / 4;
-
-        Self {
-            rows,
-            height,
-            width,
-            cube: true,
-            face_size,
-        }
+        map
     }
 
     fn flat(input: &str) -> Self {
@@ -40,16 +38,22 @@ impl Map {
         let height = rows.len() as isize;
         let width = rows[0].len() as isize;
 
-        let face_size = width / 4;
-
         Self {
             rows,
             height,
             width,
-            cube: true,
-            face_size,
+            net: None,
         }
     }
+
+    fn cell(&self, row: isize, col: isize) -> Cell {
+        if row < 0 || col < 0 || row >= self.height || col >= self.width {
+            Cell::Void
+        } else {
+            self.rows[row as usize][col as usize]
+        }
+    }
+
     fn rows_from_input(input: &str) -> Vec<Row> {
         let width = input.lines().map(|l| l.len()).max().expect("got no lines");
 
@@ -101,37 +105,7 @@ impl Map {
         }
     }
 
-    fn move_to_adjacent_face(&self, position: Position) -> Option<Position> {
-        let face_coord = (position.0 / self.face_size, position.1 / self.face_size);
-        let local_coord = (
-            position.0 - face_coord.0 * self.face_size,
-            position.1 - face_coord.1 * self.face_size,
-        );
-        let transformation = match face_coord {
-            (0, 2) => match position.2 {
-                Orientation::Up => (1, 0, Orientation::Up, -1),
-                Orientation::Right => (2, 4, Orientation::Right, 1),
-                Orientation::Left => (1, 1, Orientation::Up, 1),
-                _ => panic!("not needed (0,2)"),
-            },
-            (1, 0) => match position.2 {
-                Orientation::Up => (0, 2, Orientation::Up, -1),
-                Orientation::Left => (2, 4, Orientation::Down, -1),
-                Orientation::Down => (2, 3, Orientation::Down, -1),
-                _ => panic!("not needed (1,1)"),
-            },
-            (1, 1) => todo!(),
-            (1, 2) => todo!(),
-            (2, 3) => todo!(),
-            (3, 3) => todo!(),
-
-            (y, x) => panic!("Invalid face coordinate ({y},{x})"),
-        };
-
-        todo!()
-    }
-
-    fn do_cube_step(&self, position: Position) -> Option<Position> {
+    fn do_cube_step(&self, net: &CubeNet, position: Position) -> Option<Position> {
         let dir = match position.2 {
             Orientation::Up => (-1, 0),
             Orientation::Right => (0, 1),
@@ -139,62 +113,70 @@ impl Map {
             Orientation::Left => (0, -1),
         };
 
-        let next_pos = ((position.0 + dir.0), (position.1 + dir.1));
-
-        let move_to_adjacent_face =
-            if let Cell::Void = self.rows[next_pos.0 as usize][next_pos.1 as usize] {
-                true
-            } else if next_pos.0 < 0
-                || next_pos.1 < 0
-                || next_pos.0 == self.height
-                || next_pos.1 == self.width
-            {
-                true
-            } else {
-                false
-            };
+        let next_pos = match self.cell(position.0 + dir.0, position.1 + dir.1) {
+            Cell::Void => net.wrap(position),
+            _ => (position.0 + dir.0, position.1 + dir.1, position.2),
+        };
 
-        if move_to_adjacent_face {
-            self.move_to_adjacent_face(position)
-        } else {
-            match self.rows[next_pos.0 as usize][next_pos.1 as usize] {
-                Cell::Open => Some((next_pos.0, next_pos.1, position.2)),
-                Cell::Wall => None,
-                _ => panic!("Void shouldn't happen in this case!"),
-            }
+        match self.cell(next_pos.0, next_pos.1) {
+            Cell::Open => Some(next_pos),
+            Cell::Wall => None,
+            Cell::Void => panic!("Void shouldn't happen on a cube!"),
         }
     }
 
-    fn do_move(&self, mut position: Position, distance: usize) -> Position {
+    #[cfg(test)]
+    fn do_move(&self, position: Position, distance: usize) -> Position {
+        self.trace_move(position, distance)
+            .last()
+            .copied()
+            .unwrap_or(position)
+    }
+
+    /// Every position reached while moving, stops early at a wall.
+    fn trace_move(&self, mut position: Position, distance: usize) -> Vec<Position> {
+        let mut trail = vec![];
+
         for _ in 0..distance {
-            let step_result = if self.cube {
-                self.do_cube_step(position)
-            } else {
-                self.do_step(position)
+            let step_result = match &self.net {
+                Some(net) => self.do_cube_step(net, position),
+                None => self.do_step(position),
             };
             if let Some(step_result) = step_result {
                 position = step_result;
+                trail.push(position);
             } else {
                 break;
             }
         }
 
-        position
+        trail
     }
 
-    fn do_instruction(&self, instruction: &Instruction, position: Position) -> Position {
+    /// Positions visited by the instruction, a turn only changes the facing.
+    fn do_instruction(&self, instruction: &Instruction, position: Position) -> Vec<Position> {
         match instruction {
-            Instruction::Move(dist) => self.do_move(position, *dist),
-            Instruction::RotRight => (position.0, position.1, rotate_right(&position.2)),
-            Instruction::RotLeft => (position.0, position.1, rotate_left(&position.2)),
+            Instruction::Move(dist) => self.trace_move(position, *dist),
+            Instruction::RotRight => vec![(position.0, position.1, rotate_right(&position.2))],
+            Instruction::RotLeft => vec![(position.0, position.1, rotate_left(&position.2))],
         }
     }
-    fn follow_instructions(&self, instructions: &Instructions, mut position: Position) -> Position {
+
+    /// Final position and every position on the way there, starting with `position`.
+    fn follow_instructions(
+        &self,
+        instructions: &Instructions,
+        mut position: Position,
+    ) -> (Position, Vec<Position>) {
+        let mut trail = vec![position];
+
         for i in instructions {
-            position = self.do_instruction(i, position);
+            let visited = self.do_instruction(i, position);
+            position = visited.last().copied().unwrap_or(position);
+            trail.extend(visited);
         }
 
-        position
+        (position, trail)
     }
 }
 
@@ -220,7 +202,7 @@ type Position = (isize, isize, Orientation);
 fn parse_instructions(mut input: &str) -> Instructions {
     let mut instructions = vec![];
     loop {
-        let next_rot = input.find(|c| c == 'R' || c == 'L').unwrap_or(input.len());
+        let next_rot = input.find(['R', 'L']).unwrap_or(input.len());
 
         let movement = input[0..next_rot]
             .parse::<usize>()
@@ -250,6 +232,12 @@ fn parse(input: &str) -> (Map, Instructions) {
     (Map::flat(map_input), parse_instructions(instruction_input))
 }
 
+fn parse_cube(input: &str) -> (Map, Instructions) {
+    let (map_input, instruction_input) = input.split_once("\n\n").unwrap();
+
+    (Map::cube(map_input), parse_instructions(instruction_input))
+}
+
 fn get_start_position(map: &Map) -> Position {
     (
         0,
@@ -292,11 +280,26 @@ fn calculate_password(position: Position) -> isize {
 fn main() {
     const INPUT: &str = include_str!("input");
 
+    // `cargo run -- render` draws both trails
+    let render = env::args().nth(1).is_some_and(|arg| arg == "render");
+
     let (map, instructions) = parse(INPUT);
 
-    let position = map.follow_instructions(&instructions, get_start_position(&map));
+    let (position, trail) = map.follow_instructions(&instructions, get_start_position(&map));
+
+    if render {
+        println!("{}", render_trail(&map, &trail));
+    }
+    println!("Part 1 {}", calculate_password(position));
+
+    let (map, instructions) = parse_cube(INPUT);
+
+    let (position, trail) = map.follow_instructions(&instructions, get_start_position(&map));
 
-    println!("{}", calculate_password(position));
+    if let (true, Some(net)) = (render, &map.net) {
+        print!("{}", render_cube(&map, net, &trail));
+    }
+    println!("Part 2 {}", calculate_password(position));
 }
 
 #[cfg(test)]
@@ -418,7 +421,7 @@ mod tests {
     fn test_map_with_instructions() {
         let (map, instructions) = parse(TEST);
 
-        let final_pos = map.follow_instructions(&instructions, get_start_position(&map));
+        let (final_pos, _) = map.follow_instructions(&instructions, get_start_position(&map));
 
         assert_eq!(final_pos, (5, 7, Orientation::Right));
     }
@@ -427,4 +430,14 @@ mod tests {
     fn password_is_correct() {
         assert_eq!(6032, calculate_password((5, 7, Orientation::Right)));
     }
+
+    #[test]
+    fn test_cube_with_instructions() {
+        let (map, instructions) = parse_cube(TEST);
+
+        let (final_pos, _) = map.follow_instructions(&instructions, get_start_position(&map));
+
+        assert_eq!(final_pos, (4, 6, Orientation::Up));
+        assert_eq!(calculate_password(final_pos), 5031);
+    }
 }
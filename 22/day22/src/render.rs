@@ -0,0 +1,152 @@
+use std::fmt::Write;
+
+use crate::{cube::CubeNet, Cell, Map, Orientation, Position};
+
+const ORIENTATIONS: [Orientation; 4] = [
+    Orientation::Up,
+    Orientation::Right,
+    Orientation::Down,
+    Orientation::Left,
+];
+
+/// Background colours of the six faces
+const FACE_COLOURS: [u8; 6] = [41, 42, 43, 44, 45, 46];
+
+fn arrow(orientation: Orientation) -> char {
+    match orientation {
+        Orientation::Up => '^',
+        Orientation::Right => '>',
+        Orientation::Down => 'v',
+        Orientation::Left => '<',
+    }
+}
+
+fn name(orientation: Orientation) -> &'static str {
+    match orientation {
+        Orientation::Up => "up",
+        Orientation::Right => "right",
+        Orientation::Down => "down",
+        Orientation::Left => "left",
+    }
+}
+
+/// Map characters with the last facing of every visited tile drawn over them.
+fn tiles(map: &Map, trail: &[Position]) -> Vec<Vec<char>> {
+    let mut tiles: Vec<Vec<_>> = map
+        .rows
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|cell| match cell {
+                    Cell::Open => '.',
+                    Cell::Wall => '#',
+                    Cell::Void => ' ',
+                })
+                .collect()
+        })
+        .collect();
+
+    for (row, col, orientation) in trail {
+        tiles[*row as usize][*col as usize] = arrow(*orientation);
+    }
+
+    tiles
+}
+
+/// Draws the trail like the puzzle text, e.g. `>>v#`.
+pub fn render_trail(map: &Map, trail: &[Position]) -> String {
+    tiles(map, trail)
+        .into_iter()
+        .map(|row| {
+            let row: String = row.into_iter().collect();
+            row.trim_end().to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Draws the trail on the unfolded cube with every face in its own colour.
+///
+/// Faces are numbered in reading order, the legend lists the face on the other side of each edge.
+pub fn render_cube(map: &Map, net: &CubeNet, trail: &[Position]) -> String {
+    let mut output = String::new();
+
+    for (row, tiles) in tiles(map, trail).into_iter().enumerate() {
+        let mut current = None;
+
+        for (col, tile) in tiles.into_iter().enumerate() {
+            let face = net.face_index(row as isize, col as isize);
+
+            if face != current {
+                match face {
+                    Some(face) => write!(output, "\x1b[30;{}m", FACE_COLOURS[face]).unwrap(),
+                    None => output.push_str("\x1b[0m"),
+                }
+                current = face;
+            }
+
+            output.push(tile);
+        }
+
+        writeln!(output, "\x1b[0m").unwrap();
+    }
+
+    for (face, colour) in FACE_COLOURS.iter().enumerate() {
+        let edges: Vec<_> = ORIENTATIONS
+            .iter()
+            .map(|o| format!("{} {}", name(*o), net.neighbour(face, *o) + 1))
+            .collect();
+
+        writeln!(
+            output,
+            "\x1b[30;{colour}m {} \x1b[0m {}",
+            face + 1,
+            edges.join(", ")
+        )
+        .unwrap();
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{render_cube, render_trail};
+    use crate::{get_start_position, parse, parse_cube};
+
+    const TEST: &str = include_str!("test");
+
+    #[test]
+    fn flat_trail_matches_example() {
+        const EXPECTED: &str = "        >>v#
+        .#v.
+        #.v.
+        ..v.
+...#...v..v#
+>>>v...>#.>>
+..#v...#....
+...>>>>v..#.
+        ...#....
+        .....#..
+        .#......
+        ......#.";
+        let (map, instructions) = parse(TEST);
+
+        let (_, trail) = map.follow_instructions(&instructions, get_start_position(&map));
+
+        assert_eq!(render_trail(&map, &trail), EXPECTED);
+    }
+
+    #[test]
+    fn cube_legend() {
+        let (map, instructions) = parse_cube(TEST);
+        let net = map.net.as_ref().unwrap();
+
+        let (_, trail) = map.follow_instructions(&instructions, get_start_position(&map));
+        let output = render_cube(&map, net, &trail);
+
+        assert_eq!(output.lines().count(), 12 + 6);
+        assert!(output.contains(" 1 \x1b[0m up 2, right 6, down 4, left 3"));
+        assert!(output.contains("\x1b[30;41m>>v#\x1b[0m"));
+    }
+}
@@ -1,25 +1,38 @@
-use std::{collections::HashMap, fmt::Display};
+use std::{collections::HashMap, env, fmt::Display};
 
+use rules::{Rotation, Rules};
+use storage::{DenseElves, ElfStorage, SparseElves};
 use utils_22::Coord;
 
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
-struct Elf {
-    current: Coord,
-    next: Option<Coord>,
-}
+mod rules;
+mod storage;
 
-struct ElvesMap {
-    map: HashMap<Coord, Elf>,
+#[derive(Debug, Clone)]
+struct ElvesMap<S = SparseElves> {
+    elves: S,
+    rules: Rules,
+    /// Number of rounds played so far
+    round: usize,
 }
 
-struct Proposal {
-    movement: Coord,
-    checks: [Coord; 3],
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct RoundStats {
+    /// 1-based number of the round
+    round: usize,
+    moved: usize,
+    /// Smallest rectangle containing every elf as `(min, max)`, `None` without elves
+    bounds: Option<(Coord, Coord)>,
+    empty_ground: usize,
 }
 
 impl ElvesMap {
+    #[cfg(test)]
     fn from_text(input: &str) -> Self {
-        let mut map = HashMap::new();
+        Self::with_rules(input, Rules::default())
+    }
+
+    fn with_rules(input: &str, rules: Rules) -> Self {
+        let mut elves = SparseElves::new();
 
         for (y, line) in input.lines().enumerate() {
             for (x, c) in line.chars().enumerate() {
@@ -29,174 +42,142 @@ impl ElvesMap {
 
                 let x = x as i32;
                 let y = -(y as i32);
-                let position = Coord::new(x, y);
-                map.insert(
-                    position,
-                    Elf {
-                        current: position,
-                        next: None,
-                    },
-                );
+                elves.insert(Coord::new(x, y));
             }
         }
 
-        Self { map }
+        Self {
+            elves,
+            rules,
+            round: 0,
+        }
     }
 
-    const PROPOSALS: [Proposal; 4] = [
-        Proposal {
-            movement: Coord::UP,
-            checks: [Coord::UP_LEFT, Coord::UP, Coord::UP_RIGHT],
-        },
-        Proposal {
-            movement: Coord::DOWN,
-            checks: [Coord::DOWN_LEFT, Coord::DOWN, Coord::DOWN_RIGHT],
-        },
-        Proposal {
-            movement: Coord::LEFT,
-            checks: [Coord::UP_LEFT, Coord::LEFT, Coord::DOWN_LEFT],
-        },
-        Proposal {
-            movement: Coord::RIGHT,
-            checks: [Coord::UP_RIGHT, Coord::RIGHT, Coord::DOWN_RIGHT],
-        },
-    ];
-
-    fn plan_moves(self, proposals_offset: usize) -> Self {
-        let mut result = HashMap::new();
-        'elf: for (coord, elf) in &self.map {
-            let any_neighbour = Coord::NEIGHBOURS
-                .iter()
-                .map(|n| coord + n)
-                .any(|n| self.map.contains_key(&n));
-
-            if any_neighbour {
-                for proposal_index in 0..4 {
-                    let proposal = &Self::PROPOSALS[(proposal_index + proposals_offset) % 4];
-                    let empty = proposal
-                        .checks
-                        .iter()
-                        .map(|c| coord + c)
-                        .all(|n| !self.map.contains_key(&n));
-
-                    if empty {
-                        result.insert(
-                            coord.clone(),
-                            Elf {
-                                current: *coord,
-                                next: Some(coord + &proposal.movement),
-                            },
-                        );
-                        continue 'elf;
-                    }
-                }
-            }
-            result.insert(*coord, *elf);
+    /// Copies the elves into a bitset that grows whenever an elf walks off its edge.
+    fn into_dense(self) -> ElvesMap<DenseElves> {
+        ElvesMap {
+            elves: self.elves.into_iter().collect(),
+            rules: self.rules,
+            round: self.round,
         }
-
-        Self { map: result }
     }
+}
 
-    fn execute_move(self) -> Self {
-        let mut nexts = HashMap::new();
+impl<S: ElfStorage> ElvesMap<S> {
+    /// Proposed `(from, to)` moves of every elf that wants to move this round.
+    fn plan_moves(&self) -> Vec<(Coord, Coord)> {
+        let mut plans = vec![];
 
-        for (coord, elf) in self.map {
-            let entry = match elf.next {
-                Some(next) => nexts.entry(next).or_insert(vec![]),
-                None => nexts.entry(coord).or_insert(vec![]),
-            };
-            entry.push(elf);
-        }
+        for coord in self.elves.positions() {
+            let any_neighbour = self
+                .rules
+                .neighbours
+                .iter()
+                .any(|n| self.elves.contains(&(&coord + n)));
 
-        let mut elves_map = HashMap::new();
-
-        for (coord, elves) in nexts {
-            if elves.len() > 1 {
-                for elf in elves {
-                    elves_map.insert(
-                        elf.current,
-                        Elf {
-                            current: elf.current,
-                            next: None,
-                        },
-                    );
-                }
-            } else {
-                elves_map.insert(
-                    coord,
-                    Elf {
-                        current: coord,
-                        next: None,
-                    },
-                );
+            if !any_neighbour {
+                continue;
             }
-        }
 
-        Self { map: elves_map }
-    }
+            let proposal = self.rules.proposals_in_round(self.round).find(|proposal| {
+                proposal
+                    .checks
+                    .iter()
+                    .all(|c| !self.elves.contains(&(&coord + c)))
+            });
 
-    fn run_n_rounds(mut self, n: usize) -> Self {
-        for round in 0..n {
-            self = self.plan_moves(round).execute_move()
+            if let Some(proposal) = proposal {
+                plans.push((coord, &coord + &proposal.movement));
+            }
         }
 
-        self
+        plans
     }
 
-    fn run_to_completion(mut self) -> (Self, usize) {
-        for round in 0.. {
-            self = self.plan_moves(round);
+    /// Moves every elf whose target no other elf proposed.
+    fn run_round(&mut self) -> RoundStats {
+        let plans = self.plan_moves();
 
-            let finished = self.map.iter().all(|(_, e)| e.next.is_none());
+        let mut proposed = HashMap::new();
+        for (_, to) in &plans {
+            *proposed.entry(*to).or_insert(0) += 1;
+        }
 
-            if finished {
-                return (self, round + 1);
-            }
+        let moves: Vec<_> = plans
+            .into_iter()
+            .filter(|(_, to)| proposed[to] == 1)
+            .collect();
 
-            self = self.execute_move()
+        // All elves leave before any arrives, a target may be the spot another elf just left
+        for (from, _) in &moves {
+            self.elves.remove(from);
+        }
+        for (_, to) in &moves {
+            self.elves.insert(*to);
         }
 
-        panic!("FUCK!!!");
-    }
+        self.round += 1;
 
-    fn get_min_max_coordinates(&self) -> (Coord, Coord) {
-        let mut min = Coord::new(i32::MAX, i32::MAX);
-        let mut max = Coord::new(i32::MIN, i32::MIN);
+        RoundStats {
+            round: self.round,
+            moved: moves.len(),
+            bounds: self.bounds(),
+            empty_ground: self.count_empty_tiles(),
+        }
+    }
 
-        for (coord, _elf) in &self.map {
-            min = min.min(coord);
-            max = max.max(coord);
+    #[cfg(test)]
+    fn run_n_rounds(mut self, n: usize) -> Self {
+        for _ in 0..n {
+            self.run_round();
         }
 
-        (min, max)
+        self
+    }
+
+    /// Plays at most `max_rounds` rounds until no elf moves and returns the number of that round.
+    ///
+    /// Under some rules elves keep moving forever, e.g. with `Rotation::Fixed`, those give `None`.
+    fn run_until_stable(&mut self, max_rounds: usize) -> Option<usize> {
+        (0..max_rounds)
+            .map(|_| self.run_round())
+            .find(|stats| stats.moved == 0)
+            .map(|stats| stats.round)
+    }
+
+    /// Smallest and largest corner of the rectangle around all elves.
+    fn bounds(&self) -> Option<(Coord, Coord)> {
+        self.elves
+            .positions()
+            .into_iter()
+            .fold(None, |bounds, coord| match bounds {
+                Some((min, max)) => Some((coord.min(&min), coord.max(&max))),
+                None => Some((coord, coord)),
+            })
     }
 
     fn count_empty_tiles(&self) -> usize {
-        let (min, max) = self.get_min_max_coordinates();
-        let mut counter = 0;
-        for y in min.y()..=max.y() {
-            for x in min.x()..=max.x() {
-                let coord = Coord::new(x, y);
+        let Some((min, max)) = self.bounds() else {
+            return 0;
+        };
 
-                if !self.map.contains_key(&coord) {
-                    counter += 1;
-                }
-            }
-        }
+        let area = (max.x() - min.x() + 1) as usize * (max.y() - min.y() + 1) as usize;
 
-        counter
+        area - self.elves.len()
     }
 }
 
-impl Display for ElvesMap {
+impl<S: ElfStorage> Display for ElvesMap<S> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let (min, max) = self.get_min_max_coordinates();
+        let Some((min, max)) = self.bounds() else {
+            return Ok(());
+        };
 
         for y in (min.y()..=max.y()).rev() {
             for x in min.x()..=max.x() {
-                let char_to_print = match self.map.get(&Coord::new(x, y)) {
-                    Some(_) => '#',
-                    None => '.',
+                let char_to_print = match self.elves.contains(&Coord::new(x, y)) {
+                    true => '#',
+                    false => '.',
                 };
 
                 write!(f, "{char_to_print}")?
@@ -211,20 +192,47 @@ impl Display for ElvesMap {
 fn main() {
     const INPUT: &str = include_str!("input");
 
-    let map = ElvesMap::from_text(INPUT).run_n_rounds(10);
+    // `fixed` keeps the proposal order, `stats` prints how the elves spread out in part 1
+    let args: Vec<_> = env::args().skip(1).collect();
+    let fixed = args.iter().any(|arg| arg == "fixed");
+    let mut rules = Rules::default();
+    if fixed {
+        rules.rotation = Rotation::Fixed;
+    }
 
-    let empty_count = map.count_empty_tiles();
+    let mut map = ElvesMap::with_rules(INPUT, rules.clone()).into_dense();
+    let stats: Vec<_> = (0..10).map(|_| map.run_round()).collect();
 
-    println!("Part 1 {}", empty_count);
+    if args.iter().any(|arg| arg == "stats") {
+        for stats in &stats {
+            let bounds = match stats.bounds {
+                Some((min, max)) => format!("bounding box {min} to {max}"),
+                None => "no elves".to_string(),
+            };
 
-    let (_map, round) = ElvesMap::from_text(INPUT).run_to_completion();
+            println!(
+                "round {}: {} elves moved, {bounds}, {} empty",
+                stats.round, stats.moved, stats.empty_ground
+            );
+        }
+    }
 
-    println!("Part 2 {}", round);
+    println!("Part 1 {}", stats.last().unwrap().empty_ground);
+
+    // Two elves side by side keep walking north forever without rotating proposals
+    const MAX_ROUNDS: usize = 2_000;
+    let mut map = ElvesMap::with_rules(INPUT, rules).into_dense();
+
+    match map.run_until_stable(MAX_ROUNDS) {
+        Some(round) => println!("Part 2 {round}"),
+        None => println!("Part 2 elves still moving after {MAX_ROUNDS} rounds"),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::rules::Proposal;
 
     const TEST: &str = include_str!("test");
     const MINI_TEST: &str = ".....
@@ -238,29 +246,17 @@ mod tests {
     fn parse_works() {
         let elves = ElvesMap::from_text(TEST);
 
-        assert_eq!(elves.map.len(), 22);
+        assert_eq!(elves.elves.len(), 22);
     }
 
     #[test]
     fn planning_is_correct() {
         let elves_map = ElvesMap::from_text(MINI_TEST);
 
-        let plans = elves_map.plan_moves(0);
+        let plans = elves_map.plan_moves();
 
-        assert_eq!(
-            plans.map[&Coord::new(2, -1)],
-            Elf {
-                current: Coord::new(2, -1),
-                next: Some(Coord::new(2, 0))
-            }
-        );
-        assert_eq!(
-            plans.map[&Coord::new(3, -1)],
-            Elf {
-                current: Coord::new(3, -1),
-                next: Some(Coord::new(3, 0))
-            }
-        );
+        assert!(plans.contains(&(Coord::new(2, -1), Coord::new(2, 0))));
+        assert!(plans.contains(&(Coord::new(3, -1), Coord::new(3, 0))));
     }
 
     #[test]
@@ -269,11 +265,11 @@ mod tests {
 
         let elves_map = elves_map.run_n_rounds(1);
 
-        assert!(elves_map.map.contains_key(&Coord::new(2, 0)));
-        assert!(elves_map.map.contains_key(&Coord::new(3, 0)));
-        assert!(elves_map.map.contains_key(&Coord::new(2, -2)));
-        assert!(elves_map.map.contains_key(&Coord::new(3, -3)));
-        assert!(elves_map.map.contains_key(&Coord::new(2, -4)));
+        assert!(elves_map.elves.contains(&Coord::new(2, 0)));
+        assert!(elves_map.elves.contains(&Coord::new(3, 0)));
+        assert!(elves_map.elves.contains(&Coord::new(2, -2)));
+        assert!(elves_map.elves.contains(&Coord::new(3, -3)));
+        assert!(elves_map.elves.contains(&Coord::new(2, -4)));
     }
     #[test]
     fn execute_works_two_steps() {
@@ -281,12 +277,12 @@ mod tests {
 
         let elves_map = elves_map.run_n_rounds(2);
 
-        assert!(elves_map.map.contains_key(&Coord::new(2, -5)));
-        assert!(elves_map.map.contains_key(&Coord::new(2, -1)));
-        assert!(elves_map.map.contains_key(&Coord::new(3, -1)));
-        assert!(elves_map.map.contains_key(&Coord::new(4, -3)));
+        assert!(elves_map.elves.contains(&Coord::new(2, -5)));
+        assert!(elves_map.elves.contains(&Coord::new(2, -1)));
+        assert!(elves_map.elves.contains(&Coord::new(3, -1)));
+        assert!(elves_map.elves.contains(&Coord::new(4, -3)));
 
-        assert!(elves_map.map.contains_key(&Coord::new(1, -2)));
+        assert!(elves_map.elves.contains(&Coord::new(1, -2)));
     }
 
     #[test]
@@ -295,11 +291,11 @@ mod tests {
 
         let elves_map = elves_map.run_n_rounds(3);
 
-        assert!(elves_map.map.contains_key(&Coord::new(2, 0)));
-        assert!(elves_map.map.contains_key(&Coord::new(4, -1)));
-        assert!(elves_map.map.contains_key(&Coord::new(0, -2)));
-        assert!(elves_map.map.contains_key(&Coord::new(4, -3)));
-        assert!(elves_map.map.contains_key(&Coord::new(2, -5)));
+        assert!(elves_map.elves.contains(&Coord::new(2, 0)));
+        assert!(elves_map.elves.contains(&Coord::new(4, -1)));
+        assert!(elves_map.elves.contains(&Coord::new(0, -2)));
+        assert!(elves_map.elves.contains(&Coord::new(4, -3)));
+        assert!(elves_map.elves.contains(&Coord::new(2, -5)));
     }
 
     #[test]
@@ -340,8 +336,92 @@ mod tests {
 
     #[test]
     fn test_run_to_completion() {
-        let (_map, round) = ElvesMap::from_text(TEST).run_to_completion();
+        let mut map = ElvesMap::from_text(TEST);
+        let round = map.run_until_stable(100);
+
+        assert_eq!(round, Some(20));
+    }
+
+    #[test]
+    fn dense_matches_sparse() {
+        let mut sparse = ElvesMap::from_text(TEST);
+        let mut dense = ElvesMap::from_text(TEST).into_dense();
+
+        loop {
+            let stats = sparse.run_round();
+
+            assert_eq!(stats, dense.run_round());
+            assert_eq!(sparse.to_string(), dense.to_string());
+
+            if stats.moved == 0 {
+                break;
+            }
+        }
+    }
 
-        assert_eq!(round, 20);
+    #[test]
+    fn round_stats() {
+        let mut map = ElvesMap::from_text(MINI_TEST).into_dense();
+
+        assert_eq!(
+            map.run_round(),
+            RoundStats {
+                round: 1,
+                moved: 3,
+                bounds: Some((Coord::new(2, -4), Coord::new(3, 0))),
+                empty_ground: 5,
+            }
+        );
+        assert_eq!(map.run_until_stable(100), Some(4));
+    }
+
+    #[test]
+    fn fixed_rotation() {
+        let rules = Rules {
+            rotation: Rotation::Fixed,
+            ..Rules::default()
+        };
+        let elves_map = ElvesMap::with_rules(MINI_TEST, rules.clone()).run_n_rounds(2);
+
+        // Without rotation the elves keep trying north first
+        assert!(elves_map.elves.contains(&Coord::new(2, 1)));
+        assert!(elves_map.elves.contains(&Coord::new(3, 1)));
+        assert!(elves_map.elves.contains(&Coord::new(2, -1)));
+
+        // The two elves side by side never stop walking north
+        let mut map = ElvesMap::with_rules("##", rules);
+        assert_eq!(map.run_until_stable(50), None);
+        assert_eq!(map.round, 50);
+    }
+
+    #[test]
+    fn custom_proposals() {
+        let rules = Rules {
+            proposals: vec![Proposal {
+                movement: Coord::RIGHT,
+                checks: vec![Coord::RIGHT],
+            }],
+            ..Rules::default()
+        };
+        let mut elves_map = ElvesMap::with_rules("##", rules);
+
+        let stats = elves_map.run_round();
+
+        assert_eq!(stats.moved, 1);
+        assert_eq!(elves_map.to_string(), "#.#\n");
+        assert_eq!(elves_map.run_until_stable(100), Some(2));
+    }
+
+    #[test]
+    fn empty_map() {
+        let mut map = ElvesMap::from_text("...\n...").into_dense();
+
+        let stats = map.run_round();
+
+        assert_eq!(
+            (stats.moved, stats.bounds, stats.empty_ground),
+            (0, None, 0)
+        );
+        assert_eq!(map.to_string(), "");
     }
 }
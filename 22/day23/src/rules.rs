@@ -0,0 +1,65 @@
+use utils_22::Coord;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Proposal {
+    pub movement: Coord,
+    /// Cells that have to be empty for the elf to propose the movement
+    pub checks: Vec<Coord>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rotation {
+    /// The first proposal moves to the end after every round.
+    Cycle,
+    /// Proposals are always considered in the same order.
+    Fixed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rules {
+    pub proposals: Vec<Proposal>,
+    /// An elf without another elf on any of these offsets stays where it is
+    pub neighbours: Vec<Coord>,
+    pub rotation: Rotation,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules {
+            proposals: vec![
+                Proposal {
+                    movement: Coord::UP,
+                    checks: vec![Coord::UP_LEFT, Coord::UP, Coord::UP_RIGHT],
+                },
+                Proposal {
+                    movement: Coord::DOWN,
+                    checks: vec![Coord::DOWN_LEFT, Coord::DOWN, Coord::DOWN_RIGHT],
+                },
+                Proposal {
+                    movement: Coord::LEFT,
+                    checks: vec![Coord::UP_LEFT, Coord::LEFT, Coord::DOWN_LEFT],
+                },
+                Proposal {
+                    movement: Coord::RIGHT,
+                    checks: vec![Coord::UP_RIGHT, Coord::RIGHT, Coord::DOWN_RIGHT],
+                },
+            ],
+            neighbours: Coord::NEIGHBOURS.to_vec(),
+            rotation: Rotation::Cycle,
+        }
+    }
+}
+
+impl Rules {
+    /// Proposals in the order they are considered in the 0-based `round`.
+    pub fn proposals_in_round(&self, round: usize) -> impl Iterator<Item = &Proposal> {
+        let offset = match self.rotation {
+            Rotation::Cycle if !self.proposals.is_empty() => round % self.proposals.len(),
+            _ => 0,
+        };
+
+        self.proposals[offset..]
+            .iter()
+            .chain(&self.proposals[..offset])
+    }
+}
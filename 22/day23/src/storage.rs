@@ -0,0 +1,199 @@
+use std::collections::HashSet;
+
+use utils_22::Coord;
+
+pub trait ElfStorage: Default {
+    fn contains(&self, coord: &Coord) -> bool;
+    fn insert(&mut self, coord: Coord);
+    fn remove(&mut self, coord: &Coord);
+    fn len(&self) -> usize;
+    fn positions(&self) -> Vec<Coord>;
+}
+
+pub type SparseElves = HashSet<Coord>;
+
+impl ElfStorage for SparseElves {
+    fn contains(&self, coord: &Coord) -> bool {
+        HashSet::contains(self, coord)
+    }
+
+    fn insert(&mut self, coord: Coord) {
+        HashSet::insert(self, coord);
+    }
+
+    fn remove(&mut self, coord: &Coord) {
+        HashSet::remove(self, coord);
+    }
+
+    fn len(&self) -> usize {
+        HashSet::len(self)
+    }
+
+    fn positions(&self) -> Vec<Coord> {
+        self.iter().copied().collect()
+    }
+}
+
+/// Bitset over a rectangle starting at `min` that grows when an elf leaves it.
+#[derive(Debug, Clone, Default)]
+pub struct DenseElves {
+    min: Coord,
+    width: usize,
+    height: usize,
+    bits: Vec<u64>,
+    len: usize,
+}
+
+impl DenseElves {
+    fn index(&self, coord: &Coord) -> Option<usize> {
+        let local = coord - &self.min;
+
+        if local.x() < 0 || local.y() < 0 {
+            return None;
+        }
+
+        let (x, y) = (local.x() as usize, local.y() as usize);
+
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        Some(y * self.width + x)
+    }
+
+    /// Grows the rectangle so it covers `coord` with some room to spare in every direction.
+    fn grow(&mut self, coord: &Coord) {
+        let positions = self.positions();
+        let margin = (self.width.max(self.height) / 2 + 8) as i32;
+        let margin = Coord::new(margin, margin);
+
+        let (min, max) = if self.width == 0 {
+            (*coord, *coord)
+        } else {
+            let max = &self.min + &Coord::new(self.width as i32 - 1, self.height as i32 - 1);
+            (self.min.min(coord), max.max(coord))
+        };
+
+        self.min = &min - &margin;
+        let max = &max + &margin;
+        self.width = (max.x() - self.min.x() + 1) as usize;
+        self.height = (max.y() - self.min.y() + 1) as usize;
+        self.bits = vec![0; (self.width * self.height).div_ceil(64)];
+        self.len = 0;
+
+        for position in positions {
+            self.insert(position);
+        }
+    }
+}
+
+impl ElfStorage for DenseElves {
+    fn contains(&self, coord: &Coord) -> bool {
+        self.index(coord)
+            .is_some_and(|index| self.bits[index / 64] & (1 << (index % 64)) != 0)
+    }
+
+    fn insert(&mut self, coord: Coord) {
+        let index = match self.index(&coord) {
+            Some(index) => index,
+            None => {
+                self.grow(&coord);
+                self.index(&coord).unwrap()
+            }
+        };
+
+        let word = &mut self.bits[index / 64];
+        if *word & (1 << (index % 64)) == 0 {
+            *word |= 1 << (index % 64);
+            self.len += 1;
+        }
+    }
+
+    fn remove(&mut self, coord: &Coord) {
+        if let Some(index) = self.index(coord) {
+            let word = &mut self.bits[index / 64];
+            if *word & (1 << (index % 64)) != 0 {
+                *word &= !(1 << (index % 64));
+                self.len -= 1;
+            }
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn positions(&self) -> Vec<Coord> {
+        let mut positions = Vec::with_capacity(self.len);
+
+        for (word_index, word) in self.bits.iter().enumerate() {
+            let mut word = *word;
+
+            while word != 0 {
+                let index = word_index * 64 + word.trailing_zeros() as usize;
+                word &= word - 1;
+
+                let local = Coord::new((index % self.width) as i32, (index / self.width) as i32);
+                positions.push(&self.min + &local);
+            }
+        }
+
+        positions
+    }
+}
+
+impl FromIterator<Coord> for DenseElves {
+    fn from_iter<T: IntoIterator<Item = Coord>>(iter: T) -> Self {
+        let mut elves = DenseElves::default();
+
+        for coord in iter {
+            elves.insert(coord);
+        }
+
+        elves
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use utils_22::Coord;
+
+    use super::{DenseElves, ElfStorage};
+
+    #[test]
+    fn grows_in_every_direction() {
+        let mut elves = DenseElves::default();
+
+        let coords = [
+            Coord::new(0, 0),
+            Coord::new(-100, 3),
+            Coord::new(250, -40),
+            Coord::new(7, 900),
+        ];
+
+        for coord in coords {
+            elves.insert(coord);
+        }
+        elves.insert(Coord::new(0, 0));
+
+        assert_eq!(elves.len(), 4);
+        assert!(coords.iter().all(|coord| elves.contains(coord)));
+        assert!(!elves.contains(&Coord::new(1, 0)));
+
+        let mut positions = elves.positions();
+        positions.sort_by_key(|c| (c.x(), c.y()));
+        assert_eq!(positions, [coords[1], coords[0], coords[3], coords[2]]);
+    }
+
+    #[test]
+    fn remove() {
+        let mut elves = DenseElves::default();
+
+        elves.insert(Coord::new(3, 4));
+        elves.remove(&Coord::new(3, 4));
+        elves.remove(&Coord::new(-20, 4));
+
+        assert_eq!(elves.len(), 0);
+        assert!(!elves.contains(&Coord::new(3, 4)));
+    }
+}
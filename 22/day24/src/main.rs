@@ -0,0 +1,32 @@
+use std::{env, fs, process};
+
+use search::{single_trip, there_and_back_again};
+use valley::Valley;
+
+mod search;
+mod valley;
+
+fn main() {
+    // The puzzle input is not part of the repository, so it is read at runtime: either from the
+    // path given as the first argument or from `src/input` if one was put there
+    let path = env::args()
+        .nth(1)
+        .unwrap_or_else(|| concat!(env!("CARGO_MANIFEST_DIR"), "/src/input").into());
+
+    let Ok(input) = fs::read_to_string(&path) else {
+        eprintln!("could not read {path}");
+        eprintln!("usage: day24 [input file, defaults to src/input]");
+        process::exit(1);
+    };
+
+    let valley = Valley::from(input.as_str());
+
+    println!(
+        "Part 1 {}",
+        single_trip(&valley).expect("no way through the valley")
+    );
+    println!(
+        "Part 2 {}",
+        there_and_back_again(&valley).expect("no way back through the valley")
+    );
+}
@@ -0,0 +1,84 @@
+use utils_22::{bfs, Coord};
+
+use crate::valley::Valley;
+
+/// Earliest minute the expedition can reach `to` when it sets off from `from` at minute `start`.
+///
+/// Positions are only searched once per minute of the blizzard period: after a full period the
+/// valley looks the same again, so a state seen before can not lead anywhere faster.
+pub fn shortest_time(valley: &Valley, from: Coord, to: Coord, start: usize) -> Option<usize> {
+    let path = bfs(
+        (from, start),
+        |(position, minute)| (*position, minute % valley.period),
+        |&(position, minute)| {
+            let minute = minute + 1;
+
+            Coord::ORTHOGONAL_NEIGHBOURS
+                .iter()
+                .map(move |n| &position + n)
+                .chain([position])
+                .filter(move |next| valley.is_free(next, minute))
+                .map(move |next| (next, minute))
+        },
+        |(position, _)| *position == to,
+    )?;
+
+    path.last().map(|(_, minute)| *minute)
+}
+
+/// Minute the expedition reaches the exit.
+pub fn single_trip(valley: &Valley) -> Option<usize> {
+    shortest_time(valley, valley.entrance, valley.exit, 0)
+}
+
+/// Minute the expedition reaches the exit again after going back for the snacks.
+pub fn there_and_back_again(valley: &Valley) -> Option<usize> {
+    let there = shortest_time(valley, valley.entrance, valley.exit, 0)?;
+    let back = shortest_time(valley, valley.exit, valley.entrance, there)?;
+
+    shortest_time(valley, valley.entrance, valley.exit, back)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{shortest_time, single_trip, there_and_back_again};
+    use crate::valley::Valley;
+
+    const TEST: &str = include_str!("test");
+
+    #[test]
+    fn sample_trip() {
+        let valley = Valley::from(TEST);
+
+        assert_eq!(single_trip(&valley), Some(18));
+    }
+
+    #[test]
+    fn sample_legs() {
+        let valley = Valley::from(TEST);
+
+        assert_eq!(
+            shortest_time(&valley, valley.exit, valley.entrance, 18),
+            Some(18 + 23)
+        );
+        assert_eq!(
+            shortest_time(&valley, valley.entrance, valley.exit, 41),
+            Some(41 + 13)
+        );
+    }
+
+    #[test]
+    fn sample_round_trip() {
+        let valley = Valley::from(TEST);
+
+        assert_eq!(there_and_back_again(&valley), Some(54));
+    }
+
+    #[test]
+    fn no_way_through() {
+        // The two blizzards swap places every minute and always fill the only column
+        let valley = Valley::from("#.#\n#v#\n#^#\n#.#");
+
+        assert_eq!(single_trip(&valley), None);
+    }
+}
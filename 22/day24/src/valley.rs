@@ -0,0 +1,230 @@
+use utils_22::Coord;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Blizzard {
+    /// Position at minute 0
+    pub start: Coord,
+    pub direction: Coord,
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Basin inside the walls, `(0, 0)` is its top left cell and rows go down along negative y.
+///
+/// Entrance and exit lie in the walls above and below it. Blizzards wrap around inside the basin,
+/// so their positions repeat after `period` minutes.
+#[derive(Debug, Clone)]
+pub struct Valley {
+    pub width: usize,
+    pub height: usize,
+    pub entrance: Coord,
+    pub exit: Coord,
+    pub blizzards: Vec<Blizzard>,
+    pub period: usize,
+    /// Cells covered by a blizzard, one grid per minute of the period
+    occupied: Vec<Vec<bool>>,
+}
+
+impl From<&str> for Valley {
+    fn from(input: &str) -> Self {
+        let lines: Vec<_> = input.lines().collect();
+        assert!(lines.len() > 2, "valley needs walls above and below");
+
+        let gap = |line: &str| {
+            let col = line.find('.').expect("wall without a gap") as i32;
+            col - 1
+        };
+
+        let width = lines[0].chars().count().saturating_sub(2);
+        let height = lines.len() - 2;
+        // The blizzards need room to wrap around in
+        assert!(width > 0, "valley has no columns between its walls");
+
+        let mut blizzards = vec![];
+        for (row, line) in lines[1..=height].iter().enumerate() {
+            assert_eq!(
+                line.chars().count(),
+                width + 2,
+                "row {} is not as wide as the walls above it",
+                row + 1
+            );
+
+            for (col, c) in line.chars().skip(1).take(width).enumerate() {
+                let direction = match c {
+                    '^' => Coord::UP,
+                    'v' => Coord::DOWN,
+                    '<' => Coord::LEFT,
+                    '>' => Coord::RIGHT,
+                    '.' => continue,
+                    c => panic!("invalid char {c}"),
+                };
+
+                blizzards.push(Blizzard {
+                    start: Coord::new(col as i32, -(row as i32)),
+                    direction,
+                });
+            }
+        }
+
+        Valley::new(
+            width,
+            height,
+            Coord::new(gap(lines[0]), 1),
+            Coord::new(gap(lines[height + 1]), -(height as i32)),
+            blizzards,
+        )
+    }
+}
+
+impl Valley {
+    pub fn new(
+        width: usize,
+        height: usize,
+        entrance: Coord,
+        exit: Coord,
+        blizzards: Vec<Blizzard>,
+    ) -> Self {
+        let period = width / gcd(width, height) * height;
+
+        let mut valley = Valley {
+            width,
+            height,
+            entrance,
+            exit,
+            blizzards,
+            period,
+            occupied: vec![],
+        };
+
+        valley.occupied = (0..period)
+            .map(|minute| {
+                let mut cells = vec![false; width * height];
+
+                for position in valley.blizzard_positions(minute) {
+                    let index = valley.index(&position).unwrap();
+                    cells[index] = true;
+                }
+
+                cells
+            })
+            .collect();
+
+        valley
+    }
+
+    fn index(&self, coord: &Coord) -> Option<usize> {
+        let (col, row) = (coord.x(), -coord.y());
+
+        if col < 0 || row < 0 || col as usize >= self.width || row as usize >= self.height {
+            return None;
+        }
+
+        Some(row as usize * self.width + col as usize)
+    }
+
+    /// Where every blizzard is after `minute` minutes.
+    pub fn blizzard_positions(&self, minute: usize) -> impl Iterator<Item = Coord> + '_ {
+        let (width, height) = (self.width as i64, self.height as i64);
+        let minute = minute as i64;
+
+        self.blizzards.iter().map(move |blizzard| {
+            let col = blizzard.start.x() as i64 + blizzard.direction.x() as i64 * minute;
+            let row = -blizzard.start.y() as i64 - blizzard.direction.y() as i64 * minute;
+
+            Coord::new(
+                col.rem_euclid(width) as i32,
+                -(row.rem_euclid(height) as i32),
+            )
+        })
+    }
+
+    /// Whether the expedition can stand on `coord` at the given minute.
+    pub fn is_free(&self, coord: &Coord, minute: usize) -> bool {
+        if *coord == self.entrance || *coord == self.exit {
+            return true;
+        }
+
+        match self.index(coord) {
+            Some(index) => !self.occupied[minute % self.period][index],
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use utils_22::Coord;
+
+    use super::Valley;
+
+    const SIMPLE: &str = "#.#####
+#.....#
+#>....#
+#.....#
+#...v.#
+#.....#
+#####.#";
+
+    #[test]
+    fn parse() {
+        let valley = Valley::from(include_str!("test"));
+
+        assert_eq!((valley.width, valley.height), (6, 4));
+        assert_eq!(valley.entrance, Coord::new(0, 1));
+        assert_eq!(valley.exit, Coord::new(5, -4));
+        assert_eq!(valley.blizzards.len(), 19);
+        assert_eq!(valley.period, 12);
+    }
+
+    #[test]
+    fn blizzards_wrap_around() {
+        let valley = Valley::from(SIMPLE);
+
+        assert_eq!(valley.period, 5);
+        assert!(!valley.is_free(&Coord::new(0, -1), 0));
+        assert!(!valley.is_free(&Coord::new(3, -3), 0));
+
+        // Both blizzards share a cell in minute 3
+        let positions: Vec<_> = valley.blizzard_positions(3).collect();
+        assert_eq!(positions, [Coord::new(3, -1), Coord::new(3, -1)]);
+        assert!(!valley.is_free(&Coord::new(3, -1), 3));
+        assert!(valley.is_free(&Coord::new(3, -1), 4));
+
+        assert!(!valley.is_free(&Coord::new(0, -1), 5));
+    }
+
+    #[test]
+    #[should_panic(expected = "no columns")]
+    fn valley_without_columns() {
+        let _ = Valley::from("#.\n##\n.#");
+    }
+
+    #[test]
+    #[should_panic(expected = "row 2 is not as wide")]
+    fn ragged_rows() {
+        let _ = Valley::from("#.###\n#...#\n#..\n###.#");
+    }
+
+    #[test]
+    #[should_panic(expected = "walls above and below")]
+    fn valley_without_rows() {
+        let _ = Valley::from("#.#\n#.#");
+    }
+
+    #[test]
+    fn walls_are_never_free() {
+        let valley = Valley::from(SIMPLE);
+
+        assert!(valley.is_free(&valley.entrance, 0));
+        assert!(valley.is_free(&valley.exit, 0));
+        assert!(!valley.is_free(&Coord::new(1, 1), 0));
+        assert!(!valley.is_free(&Coord::new(-1, 0), 0));
+        assert!(!valley.is_free(&Coord::new(0, -5), 0));
+    }
+}
@@ -1,88 +1,34 @@
-fn snafu_to_i64(snafu: &str) -> i64 {
-    let mut sum = 0;
+use snafu::Snafu;
 
-    let mut power = 1;
+mod snafu;
 
-    for digit in snafu.chars().rev() {
-        let factor = match digit {
-            '0' => 0,
-            '1' => 1,
-            '2' => 2,
-            '-' => -1,
-            '=' => -2,
-            c => panic!("invalid char {}", c),
-        };
-
-        sum += factor * power;
-
-        power *= 5;
-    }
-
-    sum
-}
-
-fn i64_to_snafu(integer: i64) -> String {
-    if integer == 0 {
-        return "0".into();
-    }
-
-    let mut digits = vec![];
-    let mut number = integer;
-
-    while number != 0 {
-        let digit = number / 5;
-        let remainder = number % 5;
-        digits.push(remainder);
-
-        number = digit;
-    }
-
-    digits.push(0);
-    digits.reverse();
-
-    for idx in (1..digits.len()).rev() {
-        while digits[idx] > 2 {
-            digits[idx - 1] += 1;
-            digits[idx] -= 5;
-        }
-    }
-
-    digits
-        .iter()
-        .skip_while(|d| **d == 0)
-        .map(|d| {
-            let c = match d {
-                0 => '0',
-                1 => '1',
-                2 => '2',
-                -1 => '-',
-                -2 => '=',
-                x => panic!("invalid value {}", x),
-            };
-
-            c
-        })
-        .collect()
-}
-
-fn get_snafu_sum(input: &str) -> String {
-    let sum = input.lines().map(snafu_to_i64).sum::<i64>();
-
-    let sum_snafu = i64_to_snafu(sum);
-
-    sum_snafu
+fn fuel_sum(input: &str) -> Snafu {
+    input
+        .lines()
+        .map(|line| line.parse::<Snafu>().unwrap())
+        .sum()
 }
 
 fn main() {
-    let snafu_sum = get_snafu_sum(include_str!("input"));
+    let sum = fuel_sum(include_str!("input"));
 
-    println!("Part1: '{}'", snafu_sum);
+    println!("Part1: '{}'", sum);
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn snafu_to_i64(snafu: &str) -> i64 {
+        i64::try_from(snafu.parse::<Snafu>().unwrap()).unwrap()
+    }
+
+    fn i64_to_snafu(integer: i64) -> String {
+        let snafu: Snafu = integer.into();
+
+        snafu.to_string()
+    }
+
     #[test]
     fn test_snafu_to_integer() {
         assert_eq!(snafu_to_i64("1=-0-2"), 1747);
@@ -128,7 +74,7 @@ mod tests {
 
     #[test]
     fn test_sample() {
-        let sum = get_snafu_sum(include_str!("test"));
+        let sum = fuel_sum(include_str!("test")).to_string();
 
         assert_eq!(String::from("2=-1=0"), sum);
     }
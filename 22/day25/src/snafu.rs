@@ -0,0 +1,344 @@
+use std::{
+    fmt::Display,
+    iter::Sum,
+    marker::PhantomData,
+    ops::{Add, Mul, Neg, Sub},
+    str::FromStr,
+};
+
+/// Digit symbols of a balanced base, ordered from the lowest to the highest value.
+///
+/// There has to be an odd number of symbols, the one in the middle stands for zero.
+pub trait BalancedBase {
+    const SYMBOLS: &'static [char];
+
+    fn base() -> i64 {
+        Self::SYMBOLS.len() as i64
+    }
+
+    /// Value of the largest digit, the smallest one is its negative.
+    fn max_digit() -> i64 {
+        Self::base() / 2
+    }
+}
+
+/// Balanced base 5 as used by the elves' fuel counters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Quinary;
+
+impl BalancedBase for Quinary {
+    const SYMBOLS: &'static [char] = &['=', '-', '0', '1', '2'];
+}
+
+#[cfg(test)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Ternary;
+
+#[cfg(test)]
+impl BalancedBase for Ternary {
+    const SYMBOLS: &'static [char] = &['-', '0', '+'];
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SnafuError {
+    Empty,
+    InvalidDigit(char),
+    /// The number does not fit into the target integer
+    Overflow,
+}
+
+/// Number in a balanced base, SNAFU by default.
+///
+/// Digits are stored least significant first without leading zeros, so zero has no digits at all.
+/// Arithmetic works on the digits directly and never overflows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snafu<B = Quinary> {
+    digits: Vec<i8>,
+    base: PhantomData<B>,
+}
+
+impl<B: BalancedBase> Snafu<B> {
+    /// Builds a number from column sums of any size by carrying into the next column.
+    fn from_columns(columns: Vec<i64>) -> Self {
+        assert!(
+            B::SYMBOLS.len() % 2 == 1,
+            "a balanced base needs an odd number of digits"
+        );
+
+        let base = B::base();
+        let max = B::max_digit();
+
+        let mut digits = Vec::with_capacity(columns.len() + 1);
+        let mut carry = 0;
+
+        for column in columns {
+            let value = column + carry;
+            let digit = (value + max).rem_euclid(base) - max;
+
+            carry = (value - digit) / base;
+            digits.push(digit as i8);
+        }
+
+        while carry != 0 {
+            let digit = (carry + max).rem_euclid(base) - max;
+
+            carry = (carry - digit) / base;
+            digits.push(digit as i8);
+        }
+
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+
+        Snafu {
+            digits,
+            base: PhantomData,
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    /// Same number written in another balanced base.
+    pub fn to_base<C: BalancedBase>(&self) -> Snafu<C> {
+        self.digits
+            .iter()
+            .rev()
+            .fold(Snafu::default(), |acc, digit| {
+                acc * Snafu::from(B::base()) + Snafu::from(*digit as i64)
+            })
+    }
+}
+
+impl<B> Default for Snafu<B> {
+    fn default() -> Self {
+        Snafu {
+            digits: vec![],
+            base: PhantomData,
+        }
+    }
+}
+
+impl<B: BalancedBase> FromStr for Snafu<B> {
+    type Err = SnafuError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(SnafuError::Empty);
+        }
+
+        let columns = s
+            .chars()
+            .rev()
+            .map(
+                |c| match B::SYMBOLS.iter().position(|symbol| *symbol == c) {
+                    Some(index) => Ok(index as i64 - B::max_digit()),
+                    None => Err(SnafuError::InvalidDigit(c)),
+                },
+            )
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self::from_columns(columns))
+    }
+}
+
+impl<B: BalancedBase> Display for Snafu<B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_zero() {
+            return write!(f, "{}", B::SYMBOLS[B::max_digit() as usize]);
+        }
+
+        for digit in self.digits.iter().rev() {
+            let index = *digit as i64 + B::max_digit();
+            write!(f, "{}", B::SYMBOLS[index as usize])?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<B: BalancedBase> From<i64> for Snafu<B> {
+    fn from(value: i64) -> Self {
+        let base = B::base();
+        let mut columns = vec![];
+        let mut value = value;
+
+        // Split off one plain remainder per column, carrying brings them into the balanced range
+        while value != 0 {
+            columns.push(value % base);
+            value /= base;
+        }
+
+        Self::from_columns(columns)
+    }
+}
+
+impl<B: BalancedBase> TryFrom<Snafu<B>> for i64 {
+    type Error = SnafuError;
+
+    fn try_from(value: Snafu<B>) -> Result<Self, Self::Error> {
+        // A prefix of the digits can be slightly out of range even if the whole number is not
+        let wide = value.digits.iter().rev().try_fold(0i128, |acc, digit| {
+            acc.checked_mul(B::base() as i128)
+                .and_then(|acc| acc.checked_add(*digit as i128))
+                .ok_or(SnafuError::Overflow)
+        })?;
+
+        i64::try_from(wide).map_err(|_| SnafuError::Overflow)
+    }
+}
+
+impl<B: BalancedBase> Neg for Snafu<B> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Snafu {
+            digits: self.digits.into_iter().map(|digit| -digit).collect(),
+            base: PhantomData,
+        }
+    }
+}
+
+impl<B: BalancedBase> Add for Snafu<B> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let len = self.digits.len().max(rhs.digits.len());
+
+        let columns = (0..len)
+            .map(|i| {
+                let a = self.digits.get(i).copied().unwrap_or(0);
+                let b = rhs.digits.get(i).copied().unwrap_or(0);
+
+                a as i64 + b as i64
+            })
+            .collect();
+
+        Self::from_columns(columns)
+    }
+}
+
+impl<B: BalancedBase> Sub for Snafu<B> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + -rhs
+    }
+}
+
+impl<B: BalancedBase> Mul for Snafu<B> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        if self.is_zero() || rhs.is_zero() {
+            return Snafu::default();
+        }
+
+        let mut columns = vec![0; self.digits.len() + rhs.digits.len() - 1];
+
+        for (i, a) in self.digits.iter().enumerate() {
+            for (j, b) in rhs.digits.iter().enumerate() {
+                columns[i + j] += *a as i64 * *b as i64;
+            }
+        }
+
+        Self::from_columns(columns)
+    }
+}
+
+impl<B: BalancedBase> Sum for Snafu<B> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Snafu::default(), |acc, n| acc + n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BalancedBase, Quinary, Snafu, SnafuError, Ternary};
+
+    fn snafu(s: &str) -> Snafu {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn negative_numbers() {
+        for value in [-1, -2, -3, -7, -2022, -314159265] {
+            let negative = Snafu::<Quinary>::from(value);
+
+            assert_eq!(negative, -Snafu::from(-value));
+            assert_eq!(i64::try_from(negative), Ok(value));
+        }
+
+        assert_eq!(Snafu::<Quinary>::from(-3).to_string(), "-2");
+        assert_eq!(Snafu::<Quinary>::from(-2022).to_string(), "-2--1=");
+    }
+
+    #[test]
+    fn extreme_values() {
+        for value in [i64::MIN, i64::MAX, 0] {
+            assert_eq!(i64::try_from(Snafu::<Quinary>::from(value)), Ok(value));
+        }
+
+        let too_large = Snafu::from(i64::MAX) + snafu("1");
+        assert_eq!(i64::try_from(too_large), Err(SnafuError::Overflow));
+    }
+
+    #[test]
+    fn arithmetic_matches_integers() {
+        let values = [0, 1, -1, 2, 3, 4, 37, -198, 1747, 314159265, -12345];
+
+        for a in values {
+            for b in values {
+                let (x, y) = (Snafu::<Quinary>::from(a), Snafu::from(b));
+
+                assert_eq!(x.clone() + y.clone(), Snafu::from(a + b));
+                assert_eq!(x.clone() - y.clone(), Snafu::from(a - b));
+                assert_eq!(x * y, Snafu::from(a * b));
+            }
+        }
+    }
+
+    #[test]
+    fn sum_beyond_i64() {
+        let sum: Snafu = std::iter::repeat_n(Snafu::from(i64::MAX), 5).sum();
+
+        assert_eq!(sum, Snafu::from(i64::MAX) * snafu("10"));
+        assert_eq!(
+            sum - Snafu::from(i64::MAX) * snafu("1-"),
+            Snafu::from(i64::MAX)
+        );
+    }
+
+    #[test]
+    fn invalid_input() {
+        assert_eq!("".parse::<Snafu>(), Err(SnafuError::Empty));
+        assert_eq!("1=3".parse::<Snafu>(), Err(SnafuError::InvalidDigit('3')));
+        assert_eq!("00".parse::<Snafu>(), Ok(Snafu::default()));
+        assert_eq!(snafu("002-").to_string(), "2-");
+    }
+
+    #[test]
+    #[should_panic(expected = "odd number of digits")]
+    fn even_bases_are_rejected() {
+        struct Quaternary;
+
+        impl BalancedBase for Quaternary {
+            const SYMBOLS: &'static [char] = &['-', '0', '1', '2'];
+        }
+
+        let _ = Snafu::<Quaternary>::from(7);
+    }
+
+    #[test]
+    fn balanced_ternary() {
+        let eight: Snafu<Ternary> = "+0-".parse().unwrap();
+
+        assert_eq!(i64::try_from(eight.clone()), Ok(8));
+        assert_eq!(Snafu::<Ternary>::from(-5).to_string(), "-++");
+        assert_eq!((eight.clone() * eight).to_string(), "+-+0+");
+        assert_eq!(Snafu::<Ternary>::from(0).to_string(), "0");
+        assert_eq!(snafu("1=11-2").to_base::<Ternary>(), Snafu::from(2022));
+        assert_eq!(Snafu::<Ternary>::from(-2022).to_base(), snafu("-2--1="));
+    }
+}
@@ -0,0 +1,261 @@
+use std::ops::{Index, IndexMut};
+
+/// Circular sequence that moves elements around in O(√n).
+///
+/// Elements are addressed by the handle returned from `push`, which is their insertion index and
+/// stays valid while they are moved. The order is kept in chunks of about √n handles, so finding
+/// or changing a position only has to skip whole chunks.
+#[derive(Debug, Clone)]
+pub struct CircularList<T> {
+    values: Vec<T>,
+    chunks: Vec<Vec<usize>>,
+    /// Chunk of every handle or `None` while it is removed
+    chunk_of: Vec<Option<usize>>,
+    chunk_size: usize,
+    len: usize,
+}
+
+impl<T> Default for CircularList<T> {
+    fn default() -> Self {
+        CircularList {
+            values: vec![],
+            chunks: vec![],
+            chunk_of: vec![],
+            chunk_size: 1,
+            len: 0,
+        }
+    }
+}
+
+impl<T> CircularList<T> {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Number of elements currently in the list, removed ones are not counted.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Appends `value` at the end and returns its handle.
+    pub fn push(&mut self, value: T) -> usize {
+        let handle = self.values.len();
+        self.values.push(value);
+        self.chunk_of.push(None);
+        self.insert(handle, self.len);
+
+        handle
+    }
+
+    /// Position of `handle` counted from the start or `None` if it is removed.
+    pub fn position(&self, handle: usize) -> Option<usize> {
+        let chunk = self.chunk_of[handle]?;
+        let before: usize = self.chunks[..chunk].iter().map(Vec::len).sum();
+        let offset = self.chunks[chunk]
+            .iter()
+            .position(|h| *h == handle)
+            .unwrap();
+
+        Some(before + offset)
+    }
+
+    /// Takes `handle` out of the order and returns its former position.
+    ///
+    /// The value stays available through the handle until it is inserted again.
+    pub fn remove(&mut self, handle: usize) -> usize {
+        let position = self.position(handle).expect("handle is not in the list");
+        let chunk = self.chunk_of[handle].take().unwrap();
+
+        let offset = self.chunks[chunk]
+            .iter()
+            .position(|h| *h == handle)
+            .unwrap();
+        self.chunks[chunk].remove(offset);
+        self.len -= 1;
+
+        position
+    }
+
+    /// Puts a removed `handle` back so it ends up at `index`.
+    pub fn insert(&mut self, handle: usize, index: usize) {
+        assert!(
+            self.chunk_of[handle].is_none(),
+            "handle is already in the list"
+        );
+        assert!(index <= self.len, "index {index} is out of bounds");
+
+        if self.chunks.is_empty() {
+            self.chunks.push(vec![]);
+        }
+
+        let mut before = 0;
+        let mut chunk = self.chunks.len() - 1;
+
+        for (i, handles) in self.chunks.iter().enumerate() {
+            if index <= before + handles.len() {
+                chunk = i;
+                break;
+            }
+            before += handles.len();
+        }
+
+        self.chunks[chunk].insert(index - before, handle);
+        self.chunk_of[handle] = Some(chunk);
+        self.len += 1;
+
+        if self.chunks[chunk].len() > 2 * self.chunk_size {
+            self.rebuild();
+        }
+    }
+
+    /// Moves `handle` `offset` steps along the circle, negative offsets move it backwards.
+    ///
+    /// An element that ends up in front of the first one is placed at the end instead, which is
+    /// the same spot on the circle.
+    pub fn move_by(&mut self, handle: usize, offset: isize) {
+        if offset == 0 || self.len < 2 {
+            return;
+        }
+
+        let position = self.remove(handle);
+        let index = (position as isize + offset).rem_euclid(self.len as isize) as usize;
+
+        self.insert(handle, if index == 0 { self.len } else { index });
+    }
+
+    /// Values in list order.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.chunks
+            .iter()
+            .flatten()
+            .map(|handle| &self.values[*handle])
+    }
+
+    /// Splits the order into chunks of about √n handles again.
+    fn rebuild(&mut self) {
+        let order: Vec<_> = self.chunks.iter().flatten().copied().collect();
+        self.chunk_size = order.len().isqrt().max(1);
+
+        self.chunks = order
+            .chunks(self.chunk_size)
+            .map(|handles| handles.to_vec())
+            .collect();
+
+        for (chunk, handles) in self.chunks.iter().enumerate() {
+            for handle in handles {
+                self.chunk_of[*handle] = Some(chunk);
+            }
+        }
+    }
+}
+
+impl<T> Index<usize> for CircularList<T> {
+    type Output = T;
+
+    fn index(&self, handle: usize) -> &Self::Output {
+        &self.values[handle]
+    }
+}
+
+impl<T> IndexMut<usize> for CircularList<T> {
+    fn index_mut(&mut self, handle: usize) -> &mut Self::Output {
+        &mut self.values[handle]
+    }
+}
+
+impl<T> FromIterator<T> for CircularList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let values: Vec<_> = iter.into_iter().collect();
+        let len = values.len();
+
+        let mut list = CircularList {
+            chunk_of: vec![None; len],
+            chunks: vec![(0..len).collect()],
+            values,
+            chunk_size: 1,
+            len,
+        };
+        list.rebuild();
+
+        list
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CircularList;
+
+    fn values(list: &CircularList<i32>) -> Vec<i32> {
+        list.iter().copied().collect()
+    }
+
+    #[test]
+    fn push_and_iterate() {
+        let mut list = CircularList::new();
+
+        for value in 0..20 {
+            assert_eq!(list.push(value), value as usize);
+        }
+
+        assert_eq!(list.len(), 20);
+        assert_eq!(values(&list), (0..20).collect::<Vec<_>>());
+        assert_eq!(list.position(7), Some(7));
+    }
+
+    #[test]
+    fn remove_and_insert() {
+        let mut list: CircularList<_> = (0..5).collect();
+
+        assert_eq!(list.remove(1), 1);
+        assert_eq!(list.position(1), None);
+        assert_eq!(values(&list), [0, 2, 3, 4]);
+        assert_eq!(list[1], 1);
+
+        list.insert(1, 4);
+        assert_eq!(values(&list), [0, 2, 3, 4, 1]);
+        assert_eq!(list.position(1), Some(4));
+    }
+
+    #[test]
+    fn move_wraps_around() {
+        let mut list: CircularList<_> = (0..5).collect();
+
+        list.move_by(3, 3);
+        assert_eq!(values(&list), [0, 1, 3, 2, 4]);
+
+        list.move_by(1, -2);
+        assert_eq!(values(&list), [0, 3, 2, 1, 4]);
+
+        // Landing in front of the first element puts it at the end
+        list.move_by(2, -2);
+        assert_eq!(values(&list), [0, 3, 1, 4, 2]);
+
+        // Moving by a multiple of the other elements keeps the position
+        list.move_by(4, 8);
+        assert_eq!(values(&list), [0, 3, 1, 4, 2]);
+    }
+
+    #[test]
+    fn many_moves_keep_all_elements() {
+        let mut list: CircularList<_> = (0..100).collect();
+
+        for round in 0..10 {
+            for handle in 0..100 {
+                list.move_by(handle, (handle as isize * 7 + round) % 23 - 11);
+            }
+        }
+
+        let mut sorted = values(&list);
+        sorted.sort_unstable();
+
+        assert_eq!(sorted, (0..100).collect::<Vec<_>>());
+        for handle in 0..100 {
+            let position = list.position(handle).unwrap();
+            assert_eq!(list.iter().nth(position), Some(&(handle as i32)));
+        }
+    }
+}
@@ -48,7 +48,7 @@ impl Coord {
         Coord { x: -1, y: -1 },
     ];
 
-    pub fn new(x: i32, y: i32) -> Self {
+    pub const fn new(x: i32, y: i32) -> Self {
         Coord { x, y }
     }
 
@@ -0,0 +1,220 @@
+use std::fmt::Display;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DotNode {
+    pub id: String,
+    pub label: Option<String>,
+    pub attributes: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DotEdge {
+    pub from: String,
+    pub to: String,
+    pub weight: Option<u32>,
+    pub attributes: Vec<(String, String)>,
+}
+
+/// Graph in the Graphviz DOT language, rendered through `Display`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DotGraph {
+    pub name: String,
+    pub directed: bool,
+    pub nodes: Vec<DotNode>,
+    pub edges: Vec<DotEdge>,
+}
+
+pub trait ToDot {
+    fn to_dot(&self) -> DotGraph;
+
+    fn to_dot_with_path(&self, path: &[&str]) -> DotGraph {
+        let mut graph = self.to_dot();
+        graph.highlight_path(path);
+        graph
+    }
+}
+
+const HIGHLIGHT: [(&str, &str); 2] = [("color", "red"), ("penwidth", "2")];
+
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "\\\"").replace('\n', "\\n"))
+}
+
+fn write_attributes(
+    f: &mut std::fmt::Formatter<'_>,
+    attributes: &[(String, String)],
+) -> std::fmt::Result {
+    if attributes.is_empty() {
+        return Ok(());
+    }
+
+    let attributes: Vec<_> = attributes
+        .iter()
+        .map(|(key, value)| format!("{key}={}", quote(value)))
+        .collect();
+
+    write!(f, " [{}]", attributes.join(", "))
+}
+
+fn set_attribute(attributes: &mut Vec<(String, String)>, key: &str, value: &str) {
+    match attributes.iter_mut().find(|(existing, _)| existing == key) {
+        Some((_, existing)) => *existing = value.to_string(),
+        None => attributes.push((key.to_string(), value.to_string())),
+    }
+}
+
+impl DotGraph {
+    pub fn new(name: &str, directed: bool) -> Self {
+        DotGraph {
+            name: name.to_string(),
+            directed,
+            nodes: vec![],
+            edges: vec![],
+        }
+    }
+
+    pub fn add_node(&mut self, id: &str, label: Option<&str>, attributes: &[(&str, &str)]) {
+        self.nodes.push(DotNode {
+            id: id.to_string(),
+            label: label.map(String::from),
+            attributes: attributes
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+        });
+    }
+
+    /// Adds an edge unless an undirected graph already connects both nodes.
+    pub fn add_edge(&mut self, from: &str, to: &str, weight: Option<u32>) {
+        let exists = self.edges.iter().any(|edge| {
+            (edge.from == from && edge.to == to)
+                || (!self.directed && edge.from == to && edge.to == from)
+        });
+
+        if !exists {
+            self.edges.push(DotEdge {
+                from: from.to_string(),
+                to: to.to_string(),
+                weight,
+                attributes: vec![],
+            });
+        }
+    }
+
+    /// Colours all nodes on `path` and the edges between consecutive nodes.
+    pub fn highlight_path(&mut self, path: &[&str]) {
+        for node in self
+            .nodes
+            .iter_mut()
+            .filter(|node| path.contains(&node.id.as_str()))
+        {
+            for (key, value) in HIGHLIGHT {
+                set_attribute(&mut node.attributes, key, value);
+            }
+        }
+
+        for pair in path.windows(2) {
+            let (from, to) = (pair[0], pair[1]);
+
+            let edge = self.edges.iter_mut().find(|edge| {
+                (edge.from == from && edge.to == to)
+                    || (!self.directed && edge.from == to && edge.to == from)
+            });
+
+            if let Some(edge) = edge {
+                for (key, value) in HIGHLIGHT {
+                    set_attribute(&mut edge.attributes, key, value);
+                }
+            }
+        }
+    }
+}
+
+impl Display for DotGraph {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (keyword, connector) = if self.directed {
+            ("digraph", "->")
+        } else {
+            ("graph", "--")
+        };
+
+        writeln!(f, "{keyword} {} {{", quote(&self.name))?;
+
+        for node in &self.nodes {
+            write!(f, "    {}", quote(&node.id))?;
+
+            let mut attributes = node.attributes.clone();
+            if let Some(label) = &node.label {
+                attributes.insert(0, ("label".to_string(), label.clone()));
+            }
+            write_attributes(f, &attributes)?;
+
+            writeln!(f, ";")?;
+        }
+
+        for edge in &self.edges {
+            write!(
+                f,
+                "    {} {connector} {}",
+                quote(&edge.from),
+                quote(&edge.to)
+            )?;
+
+            let mut attributes = edge.attributes.clone();
+            if let Some(weight) = edge.weight {
+                attributes.insert(0, ("label".to_string(), weight.to_string()));
+            }
+            write_attributes(f, &attributes)?;
+
+            writeln!(f, ";")?;
+        }
+
+        writeln!(f, "}}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DotGraph;
+
+    #[test]
+    fn undirected_output() {
+        const EXPECTED: &str = r#"graph "test" {
+    "a" [label="A\n\"1\""];
+    "b" [color="grey"];
+    "a" -- "b" [label="3"];
+}
+"#;
+        let mut graph = DotGraph::new("test", false);
+        graph.add_node("a", Some("A\n\"1\""), &[]);
+        graph.add_node("b", None, &[("color", "grey")]);
+        graph.add_edge("a", "b", Some(3));
+        graph.add_edge("b", "a", Some(3));
+
+        assert_eq!(graph.to_string(), EXPECTED);
+    }
+
+    #[test]
+    fn highlight_path() {
+        const EXPECTED: &str = r#"digraph "test" {
+    "a" [color="red", penwidth="2"];
+    "b" [color="red", penwidth="2"];
+    "c";
+    "a" -> "b" [color="red", penwidth="2"];
+    "b" -> "a";
+    "b" -> "c";
+}
+"#;
+        let mut graph = DotGraph::new("test", true);
+        graph.add_node("a", None, &[]);
+        graph.add_node("b", None, &[("color", "grey")]);
+        graph.add_node("c", None, &[]);
+        graph.add_edge("a", "b", None);
+        graph.add_edge("b", "a", None);
+        graph.add_edge("b", "c", None);
+
+        graph.highlight_path(&["a", "b"]);
+
+        assert_eq!(graph.to_string(), EXPECTED);
+    }
+}
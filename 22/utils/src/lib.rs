@@ -1,5 +1,11 @@
+mod circular_list;
 mod coord;
+mod dot;
 mod line_iterator;
+mod search;
 
+pub use circular_list::CircularList;
 pub use coord::Coord;
+pub use dot::{DotEdge, DotGraph, DotNode, ToDot};
 pub use line_iterator::LineIterator;
+pub use search::bfs;
@@ -0,0 +1,86 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    hash::Hash,
+};
+
+/// Breadth first search from `start` to the first state accepted by `is_goal`.
+///
+/// States with the same `key` are only expanded once, e.g. a position at the same time within a
+/// repeating pattern. Returns every state on the way, starting with `start` and ending at the goal.
+pub fn bfs<S, Q, K, N, I, G>(start: S, key: K, mut neighbours: N, is_goal: G) -> Option<Vec<S>>
+where
+    Q: Hash + Eq,
+    K: Fn(&S) -> Q,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+    G: Fn(&S) -> bool,
+{
+    let mut seen = HashSet::from([key(&start)]);
+    // Every state that was queued with the index of the one it was reached from
+    let mut states = vec![(start, None)];
+    let mut queue = VecDeque::from([0]);
+
+    while let Some(current) = queue.pop_front() {
+        if is_goal(&states[current].0) {
+            let mut path = vec![];
+            let mut index = Some(current);
+
+            // A state is always queued after the one it was reached from
+            while let Some(i) = index {
+                states.truncate(i + 1);
+                let (state, previous) = states.pop().unwrap();
+
+                path.push(state);
+                index = previous;
+            }
+
+            path.reverse();
+            return Some(path);
+        }
+
+        for next in neighbours(&states[current].0) {
+            if seen.insert(key(&next)) {
+                states.push((next, Some(current)));
+                queue.push_back(states.len() - 1);
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::bfs;
+
+    #[test]
+    fn shortest_path() {
+        // Counting up by one or doubling, from 1 to 10
+        let path = bfs(1, |n| *n, |n| [n + 1, n * 2], |n| *n == 10).unwrap();
+
+        assert_eq!(path, [1, 2, 4, 5, 10]);
+    }
+
+    #[test]
+    fn start_is_goal() {
+        assert_eq!(bfs(3, |n| *n, |n| [n + 1], |n| *n == 3), Some(vec![3]));
+    }
+
+    #[test]
+    fn unreachable_goal() {
+        assert_eq!(bfs(0u8, |n| *n, |n| [(n + 2) % 10], |n| *n == 5), None);
+    }
+
+    #[test]
+    fn states_sharing_a_key() {
+        // Time wraps around every 3 steps, so the search ends once every position was seen
+        let path = bfs(
+            (0, 0),
+            |(position, time)| (*position, time % 3),
+            |(position, time)| [(*position, time + 1)],
+            |(position, _)| *position == 1,
+        );
+
+        assert_eq!(path, None);
+    }
+}